@@ -0,0 +1,211 @@
+use std::env;
+use std::io::stdout;
+use std::process;
+
+use helpers::{
+    bench, parse_timed, run_formatted, Answers, BenchLimit, BenchLimitError, DaySelection,
+    InputSource, OutputFormat, ParseTime, Record, Status, INPUT_ENV,
+};
+
+mod solutions;
+
+const USAGE: &str = "usage:
+    aoc run <all|N|N..M> [input file or - for stdin] [--format human|json|csv] [--record]
+    aoc bench <all|N|N..M> [runs like 100 or budget like 500ms, 2s]";
+
+enum Mode<'a> {
+    Run(Option<&'a str>),
+    Bench(BenchLimit),
+}
+
+fn take_option<'a>(args: &mut Vec<&'a str>, name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|&arg| arg == name)?;
+
+    if args.len() <= index + 1 {
+        exit_with(USAGE);
+    }
+
+    args.drain(index..=index + 1).nth(1)
+}
+
+fn take_flag(args: &mut Vec<&str>, name: &str) -> bool {
+    let index = args.iter().position(|&arg| arg == name);
+
+    if let Some(index) = index {
+        args.remove(index);
+    }
+
+    index.is_some()
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let format = take_option(&mut args, "--format")
+        .map(|format| {
+            format
+                .parse::<OutputFormat>()
+                .unwrap_or_else(|error| exit_with(&error.to_string()))
+        })
+        .unwrap_or_default();
+    let formatter = format.formatter();
+    let record = take_flag(&mut args, "--record");
+
+    let (selection, mode) = match args.as_slice() {
+        ["run", selection] => (selection, Mode::Run(None)),
+        ["run", selection, input] => (selection, Mode::Run(Some(*input))),
+        ["bench", selection] => (selection, Mode::Bench(BenchLimit::default())),
+        ["bench", selection, limit] => (
+            selection,
+            Mode::Bench(
+                limit
+                    .parse()
+                    .unwrap_or_else(|error: BenchLimitError| exit_with(&error.to_string())),
+            ),
+        ),
+        _ => exit_with(USAGE),
+    };
+    let selection = selection
+        .parse::<DaySelection>()
+        .unwrap_or_else(|error| exit_with(&error.to_string()));
+    let input_argument = match mode {
+        Mode::Run(input) => input,
+        Mode::Bench(_) => None,
+    };
+    let input_variable = env::var(INPUT_ENV).ok();
+
+    let registry = solutions::registry();
+    let days: Vec<u8> = registry
+        .days()
+        .filter(|day| selection.contains(*day))
+        .collect();
+
+    if days.is_empty() {
+        exit_with("no solutions registered for the selected days");
+    }
+
+    if let Mode::Run(_) = mode {
+        formatter
+            .header(&mut stdout())
+            .expect("Something went wrong writing the header!");
+    }
+
+    let mut failed = false;
+
+    for &day in &days {
+        let source = InputSource::select(
+            input_argument,
+            input_variable.as_deref(),
+            registry.input(day).unwrap(),
+        );
+
+        if 1 < days.len() && !source.is_bundled() {
+            exit_with("an input source can only be given when running a single day");
+        }
+
+        let input = source
+            .read()
+            .unwrap_or_else(|error| exit_with(&format!("cannot read input: {}", error)));
+
+        // Recorded answers only describe the bundled input
+        let answers_path = solutions::answers_path(day);
+        let mut answers = if source.is_bundled() {
+            Some(Answers::load(&answers_path).unwrap_or_else(|error| {
+                exit_with(&format!("{}: {}", answers_path.display(), error))
+            }))
+        } else {
+            None
+        };
+        let mut recorded = false;
+        let mut check =
+            |result: Record, answers: Option<&mut Answers>| match (result.status, answers) {
+                (Status::New, Some(answers)) if record => {
+                    answers.insert(result.part, &result.answer);
+                    recorded = true;
+                }
+                (Status::Fail, _) | (Status::Error, _) => failed = true,
+                _ => {}
+            };
+
+        for (part, solver) in registry.parts(day) {
+            match mode {
+                Mode::Run(_) => check(
+                    run_formatted(
+                        formatter.as_ref(),
+                        day,
+                        part,
+                        answers.as_ref(),
+                        ParseTime::Inline,
+                        || solver(&input),
+                    ),
+                    answers.as_mut(),
+                ),
+                Mode::Bench(limit) => bench(&format!("day{} part{}", day, part), limit, || {
+                    solver(&input)
+                }),
+            }
+        }
+
+        // Days with a parse phase parse once and time it apart from the parts
+        if let Some(parser) = registry.parser(day) {
+            match mode {
+                Mode::Run(_) => {
+                    let parsed = parse_timed(|| parser(&input));
+
+                    for (index, (part, solver)) in registry.parsed_parts(day).enumerate() {
+                        let parse = match (index, &parsed) {
+                            (0, Some(parsed)) => ParseTime::Timed(parsed.elapsed),
+                            _ => ParseTime::Shared,
+                        };
+                        let result = run_formatted(
+                            formatter.as_ref(),
+                            day,
+                            part,
+                            answers.as_ref(),
+                            parse,
+                            || {
+                                let parsed = parsed
+                                    .as_ref()
+                                    .expect("Something went wrong parsing the input!");
+
+                                solver(parsed.value.as_ref())
+                            },
+                        );
+
+                        check(result, answers.as_mut());
+                    }
+                }
+                Mode::Bench(limit) => {
+                    bench(&format!("day{} parse", day), limit, || {
+                        parser(&input);
+                        "parsed"
+                    });
+
+                    let parsed = parser(&input);
+
+                    for (part, solver) in registry.parsed_parts(day) {
+                        bench(&format!("day{} part{}", day, part), limit, || {
+                            solver(parsed.as_ref())
+                        });
+                    }
+                }
+            }
+        }
+
+        if let (true, Some(answers)) = (recorded, &answers) {
+            answers.save(&answers_path).unwrap_or_else(|error| {
+                exit_with(&format!("{}: {}", answers_path.display(), error))
+            });
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(2)
+}
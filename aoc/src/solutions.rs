@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+
+use helpers::{input_lines, parse_input, parse_split_input, Registry};
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+
+    registry
+        .add_day(1, include_str!("../../day1/input.txt"))
+        .add(1, 1, |input| day1::part1(&parse_input(input)).to_string())
+        .add(1, 2, |input| day1::part2(&parse_input(input)).to_string())
+        .add_day(2, include_str!("../../day2/input.txt"))
+        .add(2, 1, |input| day2::part1(&input_lines(input)).to_string())
+        .add(2, 2, |input| day2::part2(&input_lines(input)).to_string())
+        .add_day(3, include_str!("../../day3/input.txt"))
+        .add(3, 1, |input| day3::part1(&input_lines(input)).to_string())
+        .add(3, 2, |input| day3::part2(&input_lines(input)).to_string())
+        .add_day(4, include_str!("../../day4/input.txt"))
+        .add(4, 1, |input| day4::part1(input).to_string())
+        .add(4, 2, |input| day4::part2(input).to_string())
+        .add_day(5, include_str!("../../day5/input.txt"))
+        .add(5, 1, |input| day5::part1(&input_lines(input)).to_string())
+        .add(5, 2, |input| day5::part2(&input_lines(input)).to_string())
+        .add_day(6, include_str!("../../day6/input.txt"))
+        .add(6, 1, |input| day6::part1(input).to_string())
+        .add(6, 2, |input| day6::part2(input).to_string())
+        .add_day(7, include_str!("../../day7/input.txt"))
+        .add_parser(7, |input| day7::graphs::parse_bags(&input_lines(input)))
+        .add_parsed(7, 1, |rules| day7::graphs::part1_parsed(rules).to_string())
+        .add_parsed(7, 2, |rules| day7::graphs::part2_parsed(rules).to_string())
+        .add_day(8, include_str!("../../day8/input.txt"))
+        .add(8, 1, |input| day8::part1(input).to_string())
+        .add(8, 2, |input| day8::part2(input).to_string())
+        .add_day(9, include_str!("../../day9/input.txt"))
+        .add(9, 1, |input| {
+            day9::part1(&parse_input(input), 25).to_string()
+        })
+        .add(9, 2, |input| {
+            day9::part2(&parse_input(input), 25).to_string()
+        })
+        .add_day(10, include_str!("../../day10/input.txt"))
+        .add(10, 1, |input| day10::part1(&parse_input(input)).to_string())
+        .add(10, 2, |input| day10::part2(&parse_input(input)).to_string())
+        .add_day(11, include_str!("../../day11/input.txt"))
+        .add(11, 1, |input| day11::part1(&input_lines(input)).to_string())
+        .add(11, 2, |input| day11::part2(&input_lines(input)).to_string())
+        .add_day(12, include_str!("../../day12/input.txt"))
+        .add(12, 1, |input| day12::part1(&input_lines(input)).to_string())
+        .add(12, 2, |input| day12::part2(&input_lines(input)).to_string())
+        .add_day(13, include_str!("../../day13/input.txt"))
+        .add(13, 1, |input| day13::part1(&input_lines(input)).to_string())
+        .add(13, 2, |input| day13::part2(&input_lines(input)).to_string())
+        .add_day(14, include_str!("../../day14/input.txt"))
+        .add(14, 1, |input| day14::part1(&input_lines(input)).to_string())
+        .add(14, 2, |input| day14::part2(&input_lines(input)).to_string())
+        .add_day(15, include_str!("../../day15/input.txt"))
+        .add(15, 1, |input| {
+            day15::part1(&parse_split_input(input, ",")).to_string()
+        })
+        .add(15, 2, |input| {
+            day15::part2(&parse_split_input(input, ",")).to_string()
+        })
+        .add_day(16, include_str!("../../day16/input.txt"))
+        .add_parser(16, |input| {
+            day16::parse_notes(input).unwrap_or_else(|error| panic!("{}", error))
+        })
+        .add_parsed(16, 1, |notes| day16::part1_parsed(notes).to_string())
+        .add_parsed(16, 2, |notes| day16::part2_parsed(notes).to_string())
+        .add_day(17, include_str!("../../day17/input.txt"))
+        .add(17, 1, |input| day17::part1(&input_lines(input)).to_string())
+        .add(17, 2, |input| day17::part2(&input_lines(input)).to_string())
+        .add_day(18, include_str!("../../day18/input.txt"))
+        .add(18, 1, |input| {
+            day18::part1::solve(&input_lines(input)).to_string()
+        })
+        .add(18, 2, |input| {
+            day18::part2::solve(&input_lines(input)).to_string()
+        })
+        .add_day(19, include_str!("../../day19/input.txt"))
+        .add(19, 1, |input| day19::part1(&input_lines(input)).to_string())
+        .add(19, 2, |input| day19::part2(&input_lines(input)).to_string())
+        .add_day(20, include_str!("../../day20/input.txt"))
+        .add_parser(20, |input| {
+            day20::parse_tiles(input).unwrap_or_else(|error| panic!("{}", error))
+        })
+        .add_parsed(20, 1, |tiles: &Vec<_>| {
+            day20::part1_parsed(tiles).to_string()
+        })
+        .add_parsed(20, 2, |tiles: &Vec<_>| {
+            day20::part2_parsed(tiles).to_string()
+        })
+        .add_day(21, include_str!("../../day21/input.txt"))
+        .add(21, 1, |input| day21::part1(&input_lines(input)).to_string())
+        .add(21, 2, |input| day21::part2(&input_lines(input)).to_string())
+        .add_day(22, include_str!("../../day22/input.txt"))
+        .add(22, 1, |input| day22::part1(input).to_string())
+        .add(22, 2, |input| day22::part2(input).to_string())
+        .add_day(23, include_str!("../../day23/input.txt"))
+        .add(23, 1, |input| {
+            day23::part1(&parse_split_input(input, "")).to_string()
+        })
+        .add(23, 2, |input| {
+            day23::part2(&parse_split_input(input, "")).to_string()
+        })
+        .add_day(24, include_str!("../../day24/input.txt"))
+        .add(24, 1, |input| day24::part1(&input_lines(input)).to_string())
+        .add(24, 2, |input| day24::part2(&input_lines(input)).to_string())
+        .add_day(25, include_str!("../../day25/input.txt"))
+        .add(25, 1, |input| day25::part1(&parse_input(input)).to_string());
+
+    registry
+}
+
+pub fn answers_path(day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day{}", day))
+        .join("answers.toml")
+}
@@ -1,8 +1,9 @@
-use helpers::{parse_input, run};
+use helpers::{load_input, parse_input, run};
 use std::collections::HashSet;
 
 fn main() {
-    let input: HashSet<u64> = parse_input(include_str!("../input.txt"));
+    let raw_input = load_input(include_str!("../input.txt"));
+    let input: HashSet<u64> = parse_input(&raw_input);
 
     run("part1", || day1::part1(&input));
     run("part2", || day1::part2(&input));
@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter};
 
+use helpers::{Automaton, Grid, Neighbourhood, Rule, SquareGrid};
+
 #[derive(Debug, PartialEq, Clone)]
 enum Cell {
     Floor,
@@ -8,12 +10,12 @@ enum Cell {
 }
 
 impl Cell {
-    fn from_char(input: char) -> Cell {
+    fn from_char(input: char) -> Option<Cell> {
         match input {
-            '.' => Cell::Floor,
-            'L' => Cell::EmptySeat,
-            '#' => Cell::OccupiedSeat,
-            _ => unreachable!(),
+            '.' => Some(Cell::Floor),
+            'L' => Some(Cell::EmptySeat),
+            '#' => Some(Cell::OccupiedSeat),
+            _ => None,
         }
     }
 
@@ -24,10 +26,6 @@ impl Cell {
     fn occupied(&self) -> bool {
         self == &Cell::OccupiedSeat
     }
-
-    fn empty(&self) -> bool {
-        self == &Cell::EmptySeat
-    }
 }
 
 impl Display for Cell {
@@ -42,190 +40,44 @@ impl Display for Cell {
     }
 }
 
-type Matrix = Vec<Vec<Cell>>;
-
-struct Map {
-    matrix: Matrix,
-    height: i32,
-    width: i32,
-}
-
-impl Map {
-    fn from_input(input: &Vec<&str>) -> Self {
-        let map = input
-            .iter()
-            .map(|line| line.chars().map(|char| Cell::from_char(char)).collect())
-            .collect::<Matrix>();
-
-        let height = map.len() as i32;
-        let width = map[0].len() as i32;
-
-        Map {
-            matrix: map,
-            height,
-            width,
-        }
-    }
-
-    fn map_matrix<F>(&self, mut f: F) -> Matrix
-    where
-        F: FnMut(usize, usize, &Cell) -> Cell,
-    {
-        self.matrix
-            .iter()
-            .enumerate()
-            .map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .map(|(x, cell)| f(x, y, cell))
-                    .collect()
-            })
-            .collect::<Matrix>()
-    }
-
-    fn neighbors_coords() -> [(i32, i32); 8] {
-        [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ]
-    }
+type Map = Grid<Cell>;
 
-    fn occupation(&self) -> usize {
-        self.matrix.iter().fold(0, |sum, row| {
-            sum + row
-                .iter()
-                .fold(0, |sum, cell| sum + if cell.occupied() { 1 } else { 0 })
-        })
-    }
-
-    fn neighbor(&self, x: usize, y: usize, mx: &i32, my: &i32) -> Option<(&Cell, usize, usize)> {
-        let x = x as i32 + mx;
-        let y = y as i32 + my;
-
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return None;
-        }
-
-        let x = x as usize;
-        let y = y as usize;
-
-        Some((&self.matrix[y][x], x, y))
-    }
-
-    fn occupied_seat(&self, x: usize, y: usize, mx: &i32, my: &i32) -> usize {
-        let neighbor = self.neighbor(x, y, mx, my);
-
-        if neighbor.is_some() && neighbor.unwrap().0.occupied() {
-            1
-        } else {
-            0
-        }
-    }
-
-    fn occupied_adjacent_seats(&self, x: usize, y: usize) -> usize {
-        Self::neighbors_coords().iter().fold(0, |count, (mx, my)| {
-            count + self.occupied_seat(x, y, mx, my)
-        })
-    }
+// Far more rounds than any puzzle input needs to settle
+const MAX_ROUNDS: usize = 10_000;
 
-    fn seat_seen(&self, x: usize, y: usize, mx: &i32, my: &i32) -> usize {
-        let neighbor = self.neighbor(x, y, mx, my);
-
-        if neighbor.is_none() {
-            return 0;
-        }
-
-        let (neighbor, x, y) = neighbor.unwrap();
-
-        if false == neighbor.floor() {
-            return if neighbor.occupied() { 1 } else { 0 };
-        }
-
-        self.seat_seen(x, y, mx, my)
-    }
-
-    fn occupied_seen_seats(&self, x: usize, y: usize) -> usize {
-        Self::neighbors_coords()
-            .iter()
-            .fold(0, |count, (mx, my)| count + self.seat_seen(x, y, mx, my))
-    }
+fn parse_map(input: &Vec<&str>) -> Map {
+    Grid::from_lines(input, Cell::from_char).unwrap()
 }
 
-impl Display for Map {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in self.matrix.iter() {
-            for cell in row {
-                write!(f, "{}", cell)?;
-            }
-            writeln!(f)?;
-        }
-
-        Ok(())
-    }
+// Seats are the cells, floor never changes. Empty seats with no occupied
+// neighbours fill up, occupied ones empty once `tolerance` neighbours are.
+fn seating(map: &Map, neighbourhood: Neighbourhood, tolerance: usize) -> Automaton<SquareGrid> {
+    let topology = SquareGrid::new(map, neighbourhood, |cell| !cell.floor());
+    let survival: Vec<usize> = (0..tolerance).collect();
+    let occupied = map
+        .iter()
+        .filter(|(_, cell)| cell.occupied())
+        .map(|(point, _)| point);
+
+    Automaton::new(topology, Rule::new(&[0], &survival), occupied).unwrap()
 }
 
 pub fn part1(input: &Vec<&str>) -> usize {
-    let mut map = Map::from_input(input);
-    let mut stabilized = false;
+    let mut seating = seating(&parse_map(input), Neighbourhood::Moore, 4);
+    seating
+        .run_until_stable(MAX_ROUNDS)
+        .expect("Something went wrong, the seats never settle!");
 
-    while false == stabilized {
-        stabilized = true;
-
-        map.matrix = map.map_matrix(|x, y, cell| {
-            if cell.floor() {
-                return Cell::Floor;
-            }
-
-            let occupied_adjacent = map.occupied_adjacent_seats(x, y);
-
-            if cell.empty() && occupied_adjacent == 0 {
-                stabilized = false;
-                return Cell::OccupiedSeat;
-            }
-
-            if cell.occupied() && occupied_adjacent >= 4 {
-                stabilized = false;
-                return Cell::EmptySeat;
-            }
-
-            cell.to_owned()
-        });
-    }
-
-    map.occupation()
+    seating.population()
 }
 
 pub fn part2(input: &Vec<&str>) -> usize {
-    let mut map = Map::from_input(input);
-    let mut stabilized = false;
-
-    while false == stabilized {
-        stabilized = true;
-
-        map.matrix = map.map_matrix(|x, y, cell| {
-            let occupied_adjacent = map.occupied_seen_seats(x, y);
-
-            if cell.empty() && occupied_adjacent == 0 {
-                stabilized = false;
-                return Cell::OccupiedSeat;
-            }
-
-            if cell.occupied() && occupied_adjacent >= 5 {
-                stabilized = false;
-                return Cell::EmptySeat;
-            }
-
-            return cell.to_owned();
-        })
-    }
+    let mut seating = seating(&parse_map(input), Neighbourhood::LineOfSight, 5);
+    seating
+        .run_until_stable(MAX_ROUNDS)
+        .expect("Something went wrong, the seats never settle!");
 
-    map.occupation()
+    seating.population()
 }
 
 #[cfg(test)]
@@ -1,7 +1,8 @@
-use helpers::{input_lines, run};
+use helpers::{input_lines, load_input, run};
 
 fn main() {
-    let input: Vec<&str> = input_lines(include_str!("../input.txt"));
+    let raw_input = load_input(include_str!("../input.txt"));
+    let input: Vec<&str> = input_lines(&raw_input);
 
     run("part1", || day11::part1(&input));
     run("part2", || day11::part2(&input));
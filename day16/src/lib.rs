@@ -1,15 +1,13 @@
 #[macro_use]
 extern crate lazy_static;
 
-use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
 
-enum Reading {
-    Rules,
-    MyTicket,
-    NearbyTickets,
-}
+use helpers::{SectionError, Sections};
+use regex::Regex;
 
-type Ticket = Vec<usize>;
 type Range = (usize, usize);
 
 #[derive(PartialEq)]
@@ -18,79 +16,89 @@ struct Rule(Range, Range);
 #[derive(PartialEq)]
 struct RuleWithIndex(usize, Rule);
 
-fn parse_input(input: &Vec<&str>) -> (Vec<Rule>, Ticket, Vec<Ticket>) {
-    lazy_static! {
-        static ref RULE_REGEX: Regex =
-            Regex::new(r"^.*: (?P<fromA>\d+)-(?P<toA>\d+) or (?P<fromB>\d+)-(?P<toB>\d+)$")
-                .unwrap();
+#[derive(Debug, PartialEq)]
+pub enum NotesError {
+    Rule,
+    Number(ParseIntError),
+}
+
+impl Display for NotesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotesError::Rule => write!(f, "expected `name: A-B or C-D`"),
+            NotesError::Number(error) => write!(f, "{}", error),
+        }
     }
+}
 
-    let mut reading = Reading::Rules;
-    let mut rules = Vec::new();
-    let mut my_ticket = Vec::new();
-    let mut nearby_tickets = Vec::new();
+impl std::error::Error for NotesError {}
 
-    for line in input {
-        if line == &"your ticket:" {
-            reading = Reading::MyTicket;
-            continue;
-        }
+struct Field {
+    name: String,
+    rule: Rule,
+}
 
-        if line == &"nearby tickets:" {
-            reading = Reading::NearbyTickets;
-            continue;
-        }
+impl FromStr for Field {
+    type Err = NotesError;
 
-        match reading {
-            Reading::Rules => {
-                let captures = RULE_REGEX.captures(line).unwrap();
-                rules.push(Rule(
-                    (
-                        captures
-                            .name("fromA")
-                            .unwrap()
-                            .as_str()
-                            .parse::<usize>()
-                            .unwrap(),
-                        captures
-                            .name("toA")
-                            .unwrap()
-                            .as_str()
-                            .parse::<usize>()
-                            .unwrap(),
-                    ),
-                    (
-                        captures
-                            .name("fromB")
-                            .unwrap()
-                            .as_str()
-                            .parse::<usize>()
-                            .unwrap(),
-                        captures
-                            .name("toB")
-                            .unwrap()
-                            .as_str()
-                            .parse::<usize>()
-                            .unwrap(),
-                    ),
-                ));
-            }
-            Reading::MyTicket => {
-                my_ticket = line
-                    .split(",")
-                    .map(|part| part.parse::<usize>().unwrap())
-                    .collect();
-            }
-            Reading::NearbyTickets => {
-                nearby_tickets.push(
-                    line.split(",")
-                        .map(|part| part.parse::<usize>().unwrap())
-                        .collect::<Vec<usize>>(),
-                );
-            }
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RULE_REGEX: Regex = Regex::new(
+                r"^(?P<name>.*): (?P<fromA>\d+)-(?P<toA>\d+) or (?P<fromB>\d+)-(?P<toB>\d+)$"
+            )
+            .unwrap();
         }
+
+        let captures = RULE_REGEX.captures(input).ok_or(NotesError::Rule)?;
+        let number = |name: &str| captures[name].parse().map_err(NotesError::Number);
+
+        Ok(Field {
+            name: captures["name"].to_string(),
+            rule: Rule(
+                (number("fromA")?, number("toA")?),
+                (number("fromB")?, number("toB")?),
+            ),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct Ticket(Vec<usize>);
+
+impl FromStr for Ticket {
+    type Err = NotesError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        input
+            .split(',')
+            .map(|part| part.parse().map_err(NotesError::Number))
+            .collect::<Result<_, _>>()
+            .map(Ticket)
     }
-    (rules, my_ticket, nearby_tickets)
+}
+
+pub struct Notes {
+    names: Vec<String>,
+    rules: Vec<Rule>,
+    my_ticket: Ticket,
+    nearby_tickets: Vec<Ticket>,
+}
+
+// The rules come first, then "your ticket:" and "nearby tickets:"
+pub fn parse_notes(input: &str) -> Result<Notes, SectionError<NotesError>> {
+    let sections = Sections::new(input);
+    let fields: Vec<Field> = sections.parse_lines(0)?;
+    let (names, rules) = fields
+        .into_iter()
+        .map(|field| (field.name, field.rule))
+        .unzip();
+
+    Ok(Notes {
+        names,
+        rules,
+        my_ticket: sections.parse("your ticket")?,
+        nearby_tickets: sections.parse_lines("nearby tickets")?,
+    })
 }
 
 fn test_rule(number: &usize, rule: &Rule) -> bool {
@@ -112,7 +120,7 @@ fn solve<'a>(
         }
 
         if tickets.iter().all(|ticket| {
-            let number = ticket[deep];
+            let number = ticket.0[deep];
 
             test_rule(&number, &rule.1)
         }) {
@@ -134,14 +142,16 @@ fn solve<'a>(
     return None;
 }
 
-pub fn part1(input: &Vec<&str>) -> usize {
-    let (rules, _, nearby_tickets) = parse_input(input);
+pub fn part1(input: &str) -> usize {
+    part1_parsed(&parse_notes(input).unwrap_or_else(|error| panic!("{}", error)))
+}
 
+pub fn part1_parsed(notes: &Notes) -> usize {
     let mut ticket_scanning_error_rate = 0;
 
-    for nearby_ticket in nearby_tickets {
-        'numbers: for number in nearby_ticket {
-            for rule in rules.iter() {
+    for nearby_ticket in notes.nearby_tickets.iter() {
+        'numbers: for &number in nearby_ticket.0.iter() {
+            for rule in notes.rules.iter() {
                 if test_rule(&number, rule) {
                     continue 'numbers;
                 }
@@ -154,9 +164,14 @@ pub fn part1(input: &Vec<&str>) -> usize {
     ticket_scanning_error_rate
 }
 
-pub fn part2(input: &Vec<&str>) -> usize {
-    let (rules, my_ticket, nearby_tickets) = parse_input(input);
-    let rules_with_index = rules
+pub fn part2(input: &str) -> usize {
+    part2_parsed(&parse_notes(input).unwrap_or_else(|error| panic!("{}", error)))
+}
+
+pub fn part2_parsed(notes: &Notes) -> usize {
+    let my_ticket = &notes.my_ticket;
+    let rules_with_index = notes
+        .rules
         .iter()
         .enumerate()
         .map(|(index, rule)| RuleWithIndex(index, Rule(rule.0, rule.1)))
@@ -165,8 +180,8 @@ pub fn part2(input: &Vec<&str>) -> usize {
     let mut valid_tickets = Vec::new();
     valid_tickets.push(my_ticket.clone());
 
-    'tickets: for nearby_ticket in nearby_tickets.iter() {
-        'numbers: for number in nearby_ticket {
+    'tickets: for nearby_ticket in notes.nearby_tickets.iter() {
+        'numbers: for number in nearby_ticket.0.iter() {
             for rule_with_index in rules_with_index.iter() {
                 if test_rule(number, &rule_with_index.1) {
                     continue 'numbers;
@@ -185,10 +200,8 @@ pub fn part2(input: &Vec<&str>) -> usize {
         .iter()
         .enumerate()
         .fold(1, |solution, (index, rule)| {
-            let name = input[rule.0].split(":").collect::<Vec<&str>>()[0];
-
-            if name.starts_with("departure") {
-                return solution * my_ticket[index];
+            if notes.names[rule.0].starts_with("departure") {
+                return solution * my_ticket.0[index];
             }
 
             solution
@@ -197,8 +210,6 @@ pub fn part2(input: &Vec<&str>) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use helpers::input_lines;
-
     use super::*;
 
     #[test]
@@ -217,6 +228,24 @@ nearby tickets:
 55,2,20
 38,6,12";
 
-        assert_eq!(part1(&input_lines(input)), 71)
+        assert_eq!(part1(input), 71)
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "
+class: 1-3 or 5-7
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4,";
+
+        assert_eq!(
+            parse_notes(input).err().unwrap().to_string(),
+            "section \"nearby tickets\", line 9: cannot parse \"40,4,\": cannot parse integer from empty string"
+        );
     }
 }
@@ -1,8 +1,12 @@
-use helpers::{input_lines, run};
+use helpers::{load_input, run_parsed, run_with_parse};
 
 fn main() {
-    let input: Vec<&str> = input_lines(include_str!("../input.txt"));
+    let raw_input = load_input(include_str!("../input.txt"));
 
-    run("part1", || day16::part1(&input));
-    run("part2", || day16::part2(&input));
+    let notes = run_with_parse(
+        "part1",
+        || day16::parse_notes(&raw_input).unwrap_or_else(|error| panic!("{}", error)),
+        day16::part1_parsed,
+    );
+    run_parsed("part2", &notes, day16::part2_parsed);
 }
@@ -0,0 +1,558 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use crate::lexer::{tokenize, Token, TokenKind};
+use crate::number::Number;
+
+pub use crate::lexer::Span;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            BinaryOperator::Add => '+',
+            BinaryOperator::Subtract => '-',
+            BinaryOperator::Multiply => '*',
+            BinaryOperator::Divide => '/',
+            BinaryOperator::Remainder => '%',
+            BinaryOperator::Power => '^',
+        };
+
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+// Higher precedence binds tighter. Operators missing from the table are
+// rejected when parsing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OperatorTable {
+    pub(crate) binary: HashMap<BinaryOperator, (u8, Associativity)>,
+    pub(crate) negation: Option<u8>,
+}
+
+impl OperatorTable {
+    pub fn new() -> Self {
+        OperatorTable::default()
+    }
+
+    pub fn binary(
+        mut self,
+        operator: BinaryOperator,
+        precedence: u8,
+        associativity: Associativity,
+    ) -> Self {
+        self.binary.insert(operator, (precedence, associativity));
+        self
+    }
+
+    pub fn negation(mut self, precedence: u8) -> Self {
+        self.negation = Some(precedence);
+        self
+    }
+
+    // Part 1: `+` and `*` evaluated left to right
+    pub fn flat() -> Self {
+        OperatorTable::new()
+            .binary(BinaryOperator::Add, 1, Associativity::Left)
+            .binary(BinaryOperator::Multiply, 1, Associativity::Left)
+    }
+
+    // Part 2: `+` before `*`
+    pub fn addition_first() -> Self {
+        OperatorTable::new()
+            .binary(BinaryOperator::Add, 2, Associativity::Left)
+            .binary(BinaryOperator::Multiply, 1, Associativity::Left)
+    }
+
+    // The usual school rules, `-2 ^ 2` is `-(2 ^ 2)`
+    pub fn arithmetic() -> Self {
+        OperatorTable::new()
+            .binary(BinaryOperator::Add, 1, Associativity::Left)
+            .binary(BinaryOperator::Subtract, 1, Associativity::Left)
+            .binary(BinaryOperator::Multiply, 2, Associativity::Left)
+            .binary(BinaryOperator::Divide, 2, Associativity::Left)
+            .binary(BinaryOperator::Remainder, 2, Associativity::Left)
+            .negation(3)
+            .binary(BinaryOperator::Power, 4, Associativity::Right)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Number(String),
+    Negate(Box<Expression>),
+    Binary {
+        operator: BinaryOperator,
+        operator_span: Span,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expression {
+    pub node: Node,
+    pub span: Span,
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.node {
+            Node::Number(number) => write!(f, "{}", number),
+            Node::Negate(operand) => write!(f, "(-{})", operand),
+            Node::Binary {
+                operator,
+                left,
+                right,
+                ..
+            } => write!(f, "({} {} {})", left, operator, right),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedCharacter(char),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnclosedParenthesis,
+    UnmatchedParenthesis,
+    UnsupportedOperator(BinaryOperator),
+    InvalidNumber(String),
+    Syntax(String),
+    DivisionByZero,
+    NegativeExponent,
+    Overflow,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedCharacter(char) => write!(f, "unexpected character '{}'", char),
+            ErrorKind::UnexpectedToken(token) => write!(f, "unexpected \"{}\"", token),
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ErrorKind::UnclosedParenthesis => write!(f, "unclosed parenthesis"),
+            ErrorKind::UnmatchedParenthesis => write!(f, "unmatched closing parenthesis"),
+            ErrorKind::UnsupportedOperator(operator) => {
+                write!(f, "operator '{}' is not in the operator table", operator)
+            }
+            ErrorKind::InvalidNumber(number) => write!(f, "invalid number \"{}\"", number),
+            ErrorKind::Syntax(message) => write!(f, "{}", message),
+            ErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ErrorKind::NegativeExponent => write!(f, "negative exponent"),
+            ErrorKind::Overflow => write!(f, "overflow"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpressionError {
+    pub kind: ErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl ExpressionError {
+    pub(crate) fn new(kind: ErrorKind, offset: usize) -> Self {
+        ExpressionError {
+            kind,
+            line: 1,
+            column: offset + 1,
+        }
+    }
+}
+
+impl Display for ExpressionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+// Precedence climbing over the token list
+struct Parser<'a, 't> {
+    tokens: &'t [Token<'a>],
+    position: usize,
+    table: &'t OperatorTable,
+    length: usize,
+}
+
+impl<'a, 't> Parser<'a, 't> {
+    fn peek(&self) -> Option<&'t Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<&'t Token<'a>, ExpressionError> {
+        let token = self
+            .peek()
+            .ok_or_else(|| ExpressionError::new(ErrorKind::UnexpectedEnd, self.length))?;
+        self.position += 1;
+
+        Ok(token)
+    }
+
+    // Precedences are widened so that `u8::MAX + 1` still binds tighter
+    fn expression(&mut self, min_precedence: u16) -> Result<Expression, ExpressionError> {
+        let mut left = self.prefix()?;
+
+        while let Some(Token {
+            kind: TokenKind::Operator(operator),
+            span,
+        }) = self.peek()
+        {
+            let (precedence, associativity) =
+                *self.table.binary.get(operator).ok_or_else(|| {
+                    ExpressionError::new(ErrorKind::UnsupportedOperator(*operator), span.start)
+                })?;
+
+            let precedence = u16::from(precedence);
+
+            if precedence < min_precedence {
+                break;
+            }
+
+            self.position += 1;
+            let next_precedence = match associativity {
+                Associativity::Left => precedence + 1,
+                Associativity::Right => precedence,
+            };
+            let right = self.expression(next_precedence)?;
+
+            left = Expression {
+                span: left.span.to(right.span),
+                node: Node::Binary {
+                    operator: *operator,
+                    operator_span: *span,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn prefix(&mut self) -> Result<Expression, ExpressionError> {
+        let token = self.next()?;
+
+        match &token.kind {
+            TokenKind::Number(number) => Ok(Expression {
+                node: Node::Number(number.to_string()),
+                span: token.span,
+            }),
+            TokenKind::Operator(BinaryOperator::Subtract) if self.table.negation.is_some() => {
+                let operand = self.expression(u16::from(self.table.negation.unwrap()))?;
+
+                Ok(Expression {
+                    span: token.span.to(operand.span),
+                    node: Node::Negate(Box::new(operand)),
+                })
+            }
+            TokenKind::LeftParenthesis => {
+                let inner = self.expression(0)?;
+
+                match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::RightParenthesis,
+                        span,
+                    }) => {
+                        self.position += 1;
+
+                        Ok(Expression {
+                            node: inner.node,
+                            span: token.span.to(*span),
+                        })
+                    }
+                    _ => Err(ExpressionError::new(
+                        ErrorKind::UnclosedParenthesis,
+                        token.span.start,
+                    )),
+                }
+            }
+            TokenKind::RightParenthesis => Err(ExpressionError::new(
+                ErrorKind::UnmatchedParenthesis,
+                token.span.start,
+            )),
+            TokenKind::Operator(operator) => Err(ExpressionError::new(
+                ErrorKind::UnexpectedToken(operator.to_string()),
+                token.span.start,
+            )),
+        }
+    }
+}
+
+pub fn parse(input: &str, table: &OperatorTable) -> Result<Expression, ExpressionError> {
+    parse_tokens(&tokenize(input)?, table, input.chars().count())
+}
+
+// `length` is where the expression ends in characters, for errors about
+// missing tokens
+pub(crate) fn parse_tokens(
+    tokens: &[Token],
+    table: &OperatorTable,
+    length: usize,
+) -> Result<Expression, ExpressionError> {
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        table,
+        length,
+    };
+
+    let expression = parser.expression(0)?;
+
+    match parser.peek() {
+        None => Ok(expression),
+        Some(Token {
+            kind: TokenKind::RightParenthesis,
+            span,
+        }) => Err(ExpressionError::new(
+            ErrorKind::UnmatchedParenthesis,
+            span.start,
+        )),
+        Some(token) => Err(ExpressionError::new(
+            ErrorKind::UnexpectedToken(token.kind.to_string()),
+            token.span.start,
+        )),
+    }
+}
+
+impl Expression {
+    pub fn evaluate<N: Number>(&self) -> Result<N, ExpressionError> {
+        match &self.node {
+            Node::Number(number) => number.parse().map_err(|_| {
+                ExpressionError::new(ErrorKind::InvalidNumber(number.clone()), self.span.start)
+            }),
+            Node::Negate(operand) => operand
+                .evaluate::<N>()?
+                .checked_neg()
+                .ok_or_else(|| ExpressionError::new(ErrorKind::Overflow, self.span.start)),
+            Node::Binary {
+                operator,
+                operator_span,
+                left,
+                right,
+            } => {
+                let left: N = left.evaluate()?;
+                let right: N = right.evaluate()?;
+                let error = |kind| ExpressionError::new(kind, operator_span.start);
+
+                match operator {
+                    BinaryOperator::Add => left.checked_add(&right),
+                    BinaryOperator::Subtract => left.checked_sub(&right),
+                    BinaryOperator::Multiply => left.checked_mul(&right),
+                    BinaryOperator::Divide | BinaryOperator::Remainder if right.is_zero() => {
+                        return Err(error(ErrorKind::DivisionByZero))
+                    }
+                    BinaryOperator::Divide => left.checked_div(&right),
+                    BinaryOperator::Remainder => left.checked_rem(&right),
+                    BinaryOperator::Power if right.is_negative() => {
+                        return Err(error(ErrorKind::NegativeExponent))
+                    }
+                    BinaryOperator::Power => left.checked_pow(&right),
+                }
+                .ok_or_else(|| error(ErrorKind::Overflow))
+            }
+        }
+    }
+}
+
+pub fn evaluate<N: Number>(input: &str, table: &OperatorTable) -> Result<N, ExpressionError> {
+    parse(input, table)?.evaluate()
+}
+
+// Sum of every line, errors carry the 1-based line number
+pub fn solve<N: Number>(input: &Vec<&str>, table: &OperatorTable) -> Result<N, ExpressionError> {
+    input
+        .iter()
+        .enumerate()
+        .try_fold(N::zero(), |sum, (index, line)| {
+            let value = evaluate(line, table).map_err(|error| ExpressionError {
+                line: index + 1,
+                ..error
+            })?;
+
+            sum.checked_add(&value).ok_or(ExpressionError {
+                kind: ErrorKind::Overflow,
+                line: index + 1,
+                column: 1,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::number::BigInt;
+
+    use super::*;
+
+    fn error(input: &str, table: &OperatorTable) -> String {
+        evaluate::<i64>(input, table).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_puzzle_tables() {
+        let flat = OperatorTable::flat();
+        let addition_first = OperatorTable::addition_first();
+
+        assert_eq!(evaluate("1 + 2 * 3 + 4 * 5 + 6", &flat), Ok(71));
+        assert_eq!(
+            evaluate("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", &flat),
+            Ok(13632)
+        );
+        assert_eq!(evaluate("2 * 3 + (4 * 5)", &addition_first), Ok(46));
+        assert_eq!(
+            evaluate(
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+                &addition_first
+            ),
+            Ok(23340)
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let table = OperatorTable::arithmetic();
+
+        assert_eq!(evaluate("1 - 2 - 3", &table), Ok(-4));
+        assert_eq!(evaluate("2 ^ 3 ^ 2", &table), Ok(512));
+        assert_eq!(evaluate("-2 ^ 2", &table), Ok(-4));
+        assert_eq!(evaluate("(-2) ^ 2", &table), Ok(4));
+        assert_eq!(evaluate("7 / 2 * 2 + 7 % 2", &table), Ok(7));
+        assert_eq!(evaluate("--3 * -(1 + 1)", &table), Ok(-6));
+        assert_eq!(evaluate("12*(3+4)", &table), Ok(84));
+    }
+
+    #[test]
+    fn test_highest_precedence() {
+        let table = OperatorTable::new()
+            .binary(BinaryOperator::Subtract, 255, Associativity::Left)
+            .binary(BinaryOperator::Power, 255, Associativity::Right)
+            .binary(BinaryOperator::Add, 0, Associativity::Left)
+            .negation(255);
+
+        assert_eq!(evaluate("10 - 4 - 3", &table), Ok(3));
+        assert_eq!(evaluate("2 ^ 3 ^ 2", &table), Ok(512));
+        assert_eq!(evaluate("-2 + 10 - 1 - 1", &table), Ok(6));
+    }
+
+    #[test]
+    fn test_ast_and_spans() {
+        let expression = parse("1 + 2 * 3", &OperatorTable::addition_first()).unwrap();
+
+        assert_eq!(expression.to_string(), "((1 + 2) * 3)");
+        assert_eq!(expression.span, Span { start: 0, end: 9 });
+
+        match expression.node {
+            Node::Binary {
+                operator_span,
+                left,
+                ..
+            } => {
+                assert_eq!(operator_span.column(), 7);
+                assert_eq!(left.span, Span { start: 0, end: 5 });
+            }
+            node => panic!("unexpected node {:?}", node),
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let table = OperatorTable::arithmetic();
+
+        assert_eq!(
+            error("(1 + 2", &table),
+            "line 1, column 1: unclosed parenthesis"
+        );
+        assert_eq!(
+            error("1 + 2)", &table),
+            "line 1, column 6: unmatched closing parenthesis"
+        );
+        assert_eq!(
+            error("1 + ", &table),
+            "line 1, column 5: unexpected end of expression"
+        );
+        assert_eq!(
+            error("1 + x", &table),
+            "line 1, column 5: unexpected character 'x'"
+        );
+        assert_eq!(
+            error("4 / (2 - 2)", &table),
+            "line 1, column 3: division by zero"
+        );
+        assert_eq!(
+            error("2 ^ -1", &table),
+            "line 1, column 3: negative exponent"
+        );
+        assert_eq!(error("3 ^ 99", &table), "line 1, column 3: overflow");
+        assert_eq!(
+            error("1 - 2", &OperatorTable::flat()),
+            "line 1, column 3: operator '-' is not in the operator table"
+        );
+        assert_eq!(
+            error("-1", &OperatorTable::flat()),
+            "line 1, column 1: unexpected \"-\""
+        );
+        assert_eq!(error("1 2", &table), "line 1, column 3: unexpected \"2\"");
+    }
+
+    #[test]
+    fn test_numeric_types() {
+        let table = OperatorTable::flat();
+        let chain = vec!["9"; 25].join(" * ");
+
+        assert_eq!(
+            evaluate::<u64>(&chain, &table).unwrap_err().to_string(),
+            "line 1, column 79: overflow"
+        );
+        assert_eq!(
+            evaluate::<BigInt>(&chain, &table).map(|value| value.to_string()),
+            Ok("717897987691852588770249".to_string())
+        );
+        assert_eq!(
+            evaluate::<u64>("1 - 2", &OperatorTable::arithmetic())
+                .unwrap_err()
+                .to_string(),
+            "line 1, column 3: overflow"
+        );
+        assert_eq!(
+            evaluate::<u64>("99999999999999999999", &table)
+                .unwrap_err()
+                .to_string(),
+            "line 1, column 1: invalid number \"99999999999999999999\""
+        );
+    }
+
+    #[test]
+    fn test_solve_reports_line() {
+        let table = OperatorTable::flat();
+
+        assert_eq!(solve(&vec!["1 + 2", "3 * 4"], &table), Ok(15));
+        assert_eq!(
+            solve::<i64>(&vec!["1 + 2", "3 * (4"], &table)
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 5: unclosed parenthesis"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::expression::{BinaryOperator, ErrorKind, ExpressionError};
+
+// Character offsets into the source line, `end` excluded
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn column(&self) -> usize {
+        self.start + 1
+    }
+
+    pub(crate) fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start,
+            end: other.end,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind<'a> {
+    Number(&'a str),
+    Operator(BinaryOperator),
+    LeftParenthesis,
+    RightParenthesis,
+}
+
+impl<'a> Display for TokenKind<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Number(number) => write!(f, "{}", number),
+            TokenKind::Operator(operator) => write!(f, "{}", operator),
+            TokenKind::LeftParenthesis => write!(f, "("),
+            TokenKind::RightParenthesis => write!(f, ")"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    pub span: Span,
+}
+
+// Spaces, tabs and carriage returns separate tokens but are never required,
+// the same set as `WHITESPACE` in grammar.pest
+pub fn is_whitespace(char: char) -> bool {
+    matches!(char, ' ' | '\t' | '\r')
+}
+
+pub fn tokenize(input: &str) -> Result<Vec<Token<'_>>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().enumerate().peekable();
+
+    while let Some((start, (offset, char))) = chars.next() {
+        let mut end = start + 1;
+        let kind = match char {
+            char if is_whitespace(char) => continue,
+            '0'..='9' => {
+                let mut length = 1;
+
+                while let Some((_, (_, '0'..='9'))) = chars.peek() {
+                    length += 1;
+                    chars.next();
+                }
+
+                end = start + length;
+                // Digits are ASCII, one byte each
+                TokenKind::Number(&input[offset..offset + length])
+            }
+            '+' => TokenKind::Operator(BinaryOperator::Add),
+            '-' => TokenKind::Operator(BinaryOperator::Subtract),
+            '*' => TokenKind::Operator(BinaryOperator::Multiply),
+            '/' => TokenKind::Operator(BinaryOperator::Divide),
+            '%' => TokenKind::Operator(BinaryOperator::Remainder),
+            '^' => TokenKind::Operator(BinaryOperator::Power),
+            '(' => TokenKind::LeftParenthesis,
+            ')' => TokenKind::RightParenthesis,
+            char => {
+                return Err(ExpressionError::new(
+                    ErrorKind::UnexpectedCharacter(char),
+                    start,
+                ))
+            }
+        };
+        tokens.push(Token {
+            kind,
+            span: Span { start, end },
+        });
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<TokenKind<'_>> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_without_spaces() {
+        assert_eq!(
+            kinds("12*(3+4)"),
+            vec![
+                TokenKind::Number("12"),
+                TokenKind::Operator(BinaryOperator::Multiply),
+                TokenKind::LeftParenthesis,
+                TokenKind::Number("3"),
+                TokenKind::Operator(BinaryOperator::Add),
+                TokenKind::Number("4"),
+                TokenKind::RightParenthesis,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whitespace() {
+        assert_eq!(kinds("\t12 *\t( 3   +4 )  \r"), kinds("12*(3+4)"));
+        assert!(kinds(" \t ").is_empty());
+        assert_eq!(
+            tokenize("1 +\u{a0}2").unwrap_err().to_string(),
+            "line 1, column 4: unexpected character '\u{a0}'"
+        );
+    }
+
+    #[test]
+    fn test_positions() {
+        let spans: Vec<Span> = tokenize("123 +\t(45)")
+            .unwrap()
+            .into_iter()
+            .map(|token| token.span)
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                Span { start: 0, end: 3 },
+                Span { start: 4, end: 5 },
+                Span { start: 6, end: 7 },
+                Span { start: 7, end: 9 },
+                Span { start: 9, end: 10 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unexpected_character() {
+        assert_eq!(
+            tokenize("12 + 3.5").unwrap_err().to_string(),
+            "line 1, column 7: unexpected character '.'"
+        );
+        assert_eq!(
+            tokenize("12 + ö").unwrap_err().to_string(),
+            "line 1, column 6: unexpected character 'ö'"
+        );
+    }
+}
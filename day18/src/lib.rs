@@ -1,102 +1,24 @@
-use std::collections::VecDeque;
-
+pub mod expression;
+pub mod lexer;
+pub mod number;
 pub mod pest_grammar;
 
-type Tokens<'a> = VecDeque<&'a str>;
-
 pub mod part1 {
-    use super::Tokens;
-
-    pub fn solve(input: &Vec<&str>) -> usize {
-        input.iter().fold(0, |sum, line| {
-            let line = line.replace("(", "( ").replace(")", " )");
-
-            sum + expression(&mut line.split(" ").collect())
-        })
-    }
-
-    // Grammar
-    // expression = term (('+' | '*') term)*
-    // term = 0-9+ | ( expression )
-
-    fn expression(tokens: &mut Tokens) -> usize {
-        let mut lhs = term(tokens);
-
-        loop {
-            let token = tokens.pop_front().unwrap_or("");
-
-            match token {
-                "*" => {
-                    lhs = lhs * term(tokens);
-                }
-                "+" => {
-                    lhs = lhs + term(tokens);
-                }
-                _ => return lhs,
-            }
-        }
-    }
-
-    fn term(tokens: &mut Tokens) -> usize {
-        let token = tokens.pop_front().unwrap();
+    use crate::expression::{self, OperatorTable};
 
-        if token == "(" {
-            expression(tokens)
-        } else {
-            token.parse::<usize>().unwrap()
-        }
+    // `+` and `*` evaluated left to right
+    pub fn solve(input: &Vec<&str>) -> u64 {
+        expression::solve(input, &OperatorTable::flat()).unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
 pub mod part2 {
-    use super::Tokens;
-
-    pub fn solve(input: &Vec<&str>) -> usize {
-        input.iter().fold(0, |sum, line| {
-            let line = line.replace("(", "( ").replace(")", " )");
+    use crate::expression::{self, OperatorTable};
 
-            sum + expression(&mut line.split(" ").collect())
-        })
-    }
-
-    // Grammar
-    // expression = factor | factor '*' expression
-    // factor = term | term '+' expression
-    // term = 0-9+ | ( expression )
-
-    fn expression(tokens: &mut Tokens) -> usize {
-        let lhs = factor(tokens);
-
-        if tokens.len() > 0 && tokens[0] == "*" {
-            tokens.pop_front();
-            lhs * expression(tokens)
-        } else {
-            lhs
-        }
-    }
-
-    fn factor(tokens: &mut Tokens) -> usize {
-        let lhs = term(tokens);
-
-        if tokens.len() > 0 && tokens[0] == "+" {
-            tokens.pop_front();
-            lhs + factor(tokens)
-        } else {
-            lhs
-        }
-    }
-
-    fn term(tokens: &mut Tokens) -> usize {
-        let token = tokens.pop_front().unwrap();
-
-        if token == "(" {
-            let result = expression(tokens);
-            tokens.pop_front(); // It should be a ')'
-
-            result
-        } else {
-            token.parse::<usize>().unwrap()
-        }
+    // `+` evaluated before `*`
+    pub fn solve(input: &Vec<&str>) -> u64 {
+        expression::solve(input, &OperatorTable::addition_first())
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
@@ -170,4 +92,11 @@ mod tests {
             23340
         )
     }
+
+    #[test]
+    fn test_multi_digit_and_whitespace() {
+        assert_eq!(part1::solve(&vec!["12*(3+4)"]), 84);
+        assert_eq!(part1::solve(&vec!["\t12\t* (3 +\t\t4)"]), 84);
+        assert_eq!(part2::solve(&vec!["10*(3+4)+5", "  2 *  3+4 "]), 134);
+    }
 }
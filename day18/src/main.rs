@@ -1,17 +1,30 @@
+use day18::expression::{self, OperatorTable};
+use day18::number::BigInt;
 use day18::pest_grammar;
-use helpers::{input_lines, run};
+use helpers::{input_lines, load_input, run};
 
 fn main() {
-    let raw_input = include_str!("../input.txt");
-    let input: Vec<&str> = input_lines(raw_input);
+    let raw_input = load_input(include_str!("../input.txt"));
+    let input: Vec<&str> = input_lines(&raw_input);
 
     run("part1", || day18::part1::solve(&input));
     run("part2", || day18::part2::solve(&input));
 
     run("part1 (pest_grammar)", || {
-        pest_grammar::solve(raw_input, false)
+        pest_grammar::solve::<u64>(&raw_input, &OperatorTable::flat())
+            .unwrap_or_else(|error| panic!("{}", error))
     });
     run("part2 (pest_grammar)", || {
-        pest_grammar::solve(raw_input, true)
+        pest_grammar::solve::<u64>(&raw_input, &OperatorTable::addition_first())
+            .unwrap_or_else(|error| panic!("{}", error))
+    });
+
+    run("part1 (expression, big integer)", || {
+        expression::solve::<BigInt>(&input, &OperatorTable::flat())
+            .unwrap_or_else(|error| panic!("{}", error))
+    });
+    run("part2 (expression, big integer)", || {
+        expression::solve::<BigInt>(&input, &OperatorTable::addition_first())
+            .unwrap_or_else(|error| panic!("{}", error))
     });
 }
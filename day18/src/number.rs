@@ -0,0 +1,247 @@
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use num_bigint::Sign;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+pub use num_bigint::BigInt;
+
+// What the expression evaluator needs from a number. Every operation returns
+// `None` when the result does not fit, division by zero and negative
+// exponents are checked by the caller.
+pub trait Number: Clone + Display + FromStr {
+    fn zero() -> Self;
+    fn is_zero(&self) -> bool;
+    fn is_negative(&self) -> bool;
+    fn checked_neg(&self) -> Option<Self>;
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+    fn checked_div(&self, other: &Self) -> Option<Self>;
+    fn checked_rem(&self, other: &Self) -> Option<Self>;
+    fn checked_pow(&self, exponent: &Self) -> Option<Self>;
+}
+
+macro_rules! primitive_number {
+    ($type:ty, $is_negative:expr) => {
+        impl Number for $type {
+            fn zero() -> Self {
+                0
+            }
+
+            fn is_zero(&self) -> bool {
+                *self == 0
+            }
+
+            fn is_negative(&self) -> bool {
+                $is_negative(*self)
+            }
+
+            fn checked_neg(&self) -> Option<Self> {
+                <$type>::checked_neg(*self)
+            }
+
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                <$type>::checked_add(*self, *other)
+            }
+
+            fn checked_sub(&self, other: &Self) -> Option<Self> {
+                <$type>::checked_sub(*self, *other)
+            }
+
+            fn checked_mul(&self, other: &Self) -> Option<Self> {
+                <$type>::checked_mul(*self, *other)
+            }
+
+            fn checked_div(&self, other: &Self) -> Option<Self> {
+                <$type>::checked_div(*self, *other)
+            }
+
+            fn checked_rem(&self, other: &Self) -> Option<Self> {
+                <$type>::checked_rem(*self, *other)
+            }
+
+            fn checked_pow(&self, exponent: &Self) -> Option<Self> {
+                u32::try_from(*exponent)
+                    .ok()
+                    .and_then(|exponent| <$type>::checked_pow(*self, exponent))
+            }
+        }
+    };
+}
+
+primitive_number!(i32, |number: i32| number < 0);
+primitive_number!(i64, |number: i64| number < 0);
+primitive_number!(i128, |number: i128| number < 0);
+primitive_number!(u32, |_| false);
+primitive_number!(u64, |_| false);
+primitive_number!(u128, |_| false);
+
+// Powers past this many bits count as an overflow, so that a huge exponent
+// fails right away instead of running out of memory
+const MAX_POW_BITS: u64 = 1 << 24;
+
+impl Number for BigInt {
+    fn zero() -> Self {
+        Zero::zero()
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn is_negative(&self) -> bool {
+        self.sign() == Sign::Minus
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-self)
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Some(self - other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    // Truncated like the primitive types, the remainder takes the sign of `self`
+    fn checked_div(&self, other: &Self) -> Option<Self> {
+        match Zero::is_zero(other) {
+            true => None,
+            false => Some(self / other),
+        }
+    }
+
+    fn checked_rem(&self, other: &Self) -> Option<Self> {
+        match Zero::is_zero(other) {
+            true => None,
+            false => Some(self % other),
+        }
+    }
+
+    // Any exponent is fine for 0, 1 and -1, other bases are limited by the
+    // size of the result
+    fn checked_pow(&self, exponent: &Self) -> Option<Self> {
+        if Number::is_negative(exponent) {
+            return None;
+        }
+
+        if self.magnitude().is_one() || Zero::is_zero(self) {
+            return Some(match (Zero::is_zero(exponent), exponent.bit(0)) {
+                (true, _) => One::one(),
+                (false, false) => self.abs(),
+                (false, true) => self.clone(),
+            });
+        }
+
+        let exponent = exponent.to_u32()?;
+
+        if MAX_POW_BITS < self.bits().saturating_mul(exponent as u64) {
+            return None;
+        }
+
+        Some(self.pow(exponent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use helpers::Random;
+
+    use super::*;
+
+    fn big(input: &str) -> BigInt {
+        input.parse().unwrap()
+    }
+
+    fn numbers(count: usize) -> Vec<i64> {
+        let mut random = Random::new(0x9e37_79b9_7f4a_7c15);
+
+        (0..count)
+            .map(|index| {
+                // Mix in some small values to cover single digit divisors
+                match index % 4 {
+                    0 => random.below(100) as i64 - 50,
+                    _ => random.next_u64() as i64 >> (index % 3 * 16),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(
+            big("-123456789012345678901234567890").to_string(),
+            "-123456789012345678901234567890"
+        );
+        assert_eq!(format!("{:>6}", big("-42")), "   -42");
+        assert!("12a".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn test_matches_i128() {
+        let numbers = numbers(60);
+
+        for &a in numbers.iter() {
+            for &b in numbers.iter() {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                let (a, b) = (a as i128, b as i128);
+
+                assert_eq!(x.checked_add(&y).unwrap().to_string(), (a + b).to_string());
+                assert_eq!(x.checked_sub(&y).unwrap().to_string(), (a - b).to_string());
+                assert_eq!(x.checked_mul(&y).unwrap().to_string(), (a * b).to_string());
+
+                if b != 0 {
+                    assert_eq!(x.checked_div(&y).unwrap().to_string(), (a / b).to_string());
+                    assert_eq!(x.checked_rem(&y).unwrap().to_string(), (a % b).to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_values() {
+        let power = big("3").checked_pow(&big("100")).unwrap();
+
+        assert_eq!(
+            power.to_string(),
+            "515377520732011331036461129765621272702107522001"
+        );
+        assert_eq!(
+            power.checked_div(&big("3").checked_pow(&big("98")).unwrap()),
+            Some(big("9"))
+        );
+        assert_eq!(
+            power.checked_rem(&big("1000000007")),
+            Some(big("886041711"))
+        );
+        assert_eq!(big("-1").checked_pow(&big("99999999999")), Some(big("-1")));
+        assert_eq!(big("-1").checked_pow(&big("0")), Some(big("1")));
+        assert_eq!(big("-1").checked_pow(&big("2")), Some(big("1")));
+        assert_eq!(big("0").checked_pow(&big("99999999999")), Some(big("0")));
+        assert_eq!(big("2").checked_pow(&big("99999999999")), None);
+        assert_eq!(big("2").checked_pow(&big("4294967295")), None);
+        assert_eq!(big("-2").checked_pow(&big("3")), Some(big("-8")));
+        assert_eq!(big("5").checked_div(&Number::zero()), None);
+    }
+
+    #[test]
+    fn test_primitive_overflow() {
+        assert_eq!(Number::checked_mul(&u64::MAX, &2), None);
+        assert_eq!(Number::checked_neg(&1u64), None);
+        assert_eq!(Number::checked_pow(&2u64, &64), None);
+        assert_eq!(Number::checked_pow(&2i64, &-1), None);
+        assert!(Number::is_negative(&-1i64));
+    }
+}
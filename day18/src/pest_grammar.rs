@@ -1,133 +1,433 @@
-use pest::iterators::{Pair, Pairs};
-use pest::prec_climber::{Assoc, Operator, PrecClimber};
+use std::collections::BTreeMap;
+
+use pest::error::LineColLocation;
+use pest::iterators::Pair;
+use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest::Parser;
 use pest_derive::Parser;
 
+use crate::expression::{
+    Associativity, BinaryOperator, ErrorKind, Expression, ExpressionError, Node, OperatorTable,
+    Span,
+};
+use crate::number::Number;
+
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 pub struct CalcParser;
 
-fn eval(expression: Pairs<Rule>, prec_climber: &PrecClimber<Rule>) -> usize {
-    prec_climber.climb(
-        expression,
-        |pair: Pair<Rule>| match pair.as_rule() {
-            Rule::num => pair.as_str().parse::<usize>().unwrap(),
-            Rule::expr => eval(pair.into_inner(), prec_climber),
-            _ => unreachable!(),
-        },
-        |lhs: usize, op: Pair<Rule>, rhs: usize| match op.as_rule() {
-            Rule::add => lhs + rhs,
-            Rule::multiply => lhs * rhs,
-            _ => unreachable!(),
-        },
-    )
+fn rule(operator: BinaryOperator) -> Rule {
+    match operator {
+        BinaryOperator::Add => Rule::add,
+        BinaryOperator::Subtract => Rule::subtract,
+        BinaryOperator::Multiply => Rule::multiply,
+        BinaryOperator::Divide => Rule::divide,
+        BinaryOperator::Remainder => Rule::remainder,
+        BinaryOperator::Power => Rule::power,
+    }
 }
 
-pub fn solve(input: &str, multiply_precedence: bool) -> usize {
-    let file = CalcParser::parse(Rule::file, input)
-        .unwrap()
-        .next()
-        .unwrap();
+fn operator(rule: Rule) -> Option<BinaryOperator> {
+    match rule {
+        Rule::add => Some(BinaryOperator::Add),
+        Rule::subtract => Some(BinaryOperator::Subtract),
+        Rule::multiply => Some(BinaryOperator::Multiply),
+        Rule::divide => Some(BinaryOperator::Divide),
+        Rule::remainder => Some(BinaryOperator::Remainder),
+        Rule::power => Some(BinaryOperator::Power),
+        _ => None,
+    }
+}
+
+// One level per distinct precedence in the table, lowest first, so operators
+// bind the same way as with the handwritten parser
+fn pratt_parser(table: &OperatorTable) -> PrattParser<Rule> {
+    let mut levels: BTreeMap<u8, Vec<Op<Rule>>> = BTreeMap::new();
 
-    let prec_climber: PrecClimber<Rule> = {
-        use Assoc::*;
-        use Rule::*;
-
-        if multiply_precedence {
-            PrecClimber::new(vec![
-                Operator::new(multiply, Left),
-                Operator::new(add, Left),
-            ])
-        } else {
-            PrecClimber::new(vec![
-                Operator::new(multiply, Left) | Operator::new(add, Left),
-            ])
+    for (&operator, &(precedence, associativity)) in table.binary.iter() {
+        let associativity = match associativity {
+            Associativity::Left => Assoc::Left,
+            Associativity::Right => Assoc::Right,
+        };
+
+        levels
+            .entry(precedence)
+            .or_default()
+            .push(Op::infix(rule(operator), associativity));
+    }
+
+    if let Some(precedence) = table.negation {
+        levels
+            .entry(precedence)
+            .or_default()
+            .push(Op::prefix(Rule::negate));
+    }
+
+    levels
+        .into_iter()
+        .fold(PrattParser::new(), |parser, (_, operators)| {
+            parser.op(operators
+                .into_iter()
+                .reduce(|level, op| level | op)
+                .unwrap())
+        })
+}
+
+// Builds the expressions of one line, spans are character offsets into the
+// line like the ones from the handwritten lexer
+struct Builder<'a> {
+    table: &'a OperatorTable,
+    pratt: &'a PrattParser<Rule>,
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Builder<'a> {
+    fn span(&self, pair: &Pair<Rule>) -> Span {
+        let span = pair.as_span();
+        let start = self.text[..span.start() - self.offset].chars().count();
+
+        Span {
+            start,
+            end: start + span.as_str().chars().count(),
         }
-    };
+    }
 
-    let mut sum = 0;
+    // The Pratt parser only knows the operators in the table, anything else
+    // is reported like the handwritten parser does
+    fn check(&self, expr: &Pair<Rule>) -> Result<(), ExpressionError> {
+        expr.clone().into_inner().flatten().try_for_each(|pair| {
+            let error = |kind| Err(ExpressionError::new(kind, self.span(&pair).start));
 
-    for line in file.into_inner() {
-        match line.as_rule() {
-            Rule::line => {
-                sum = sum + eval(line.into_inner(), &prec_climber);
+            match (pair.as_rule(), operator(pair.as_rule())) {
+                (_, Some(operator)) if !self.table.binary.contains_key(&operator) => {
+                    error(ErrorKind::UnsupportedOperator(operator))
+                }
+                (Rule::negate, _) if self.table.negation.is_none() => {
+                    error(ErrorKind::UnexpectedToken(pair.as_str().to_string()))
+                }
+                _ => Ok(()),
             }
-            Rule::EOI => (),
-            _ => unreachable!(),
-        }
+        })
     }
 
-    sum
+    fn expression(&self, expr: Pair<Rule>) -> Expression {
+        self.pratt
+            .map_primary(|primary| match primary.as_rule() {
+                Rule::num => Expression {
+                    node: Node::Number(primary.as_str().to_string()),
+                    span: self.span(&primary),
+                },
+                Rule::group => {
+                    let span = self.span(&primary);
+                    let inner = self.expression(primary.into_inner().nth(1).unwrap());
+
+                    Expression {
+                        node: inner.node,
+                        span,
+                    }
+                }
+                rule => unreachable!("unexpected {:?}", rule),
+            })
+            .map_prefix(|negate, operand| Expression {
+                span: self.span(&negate).to(operand.span),
+                node: Node::Negate(Box::new(operand)),
+            })
+            .map_infix(|left, pair, right| Expression {
+                span: left.span.to(right.span),
+                node: Node::Binary {
+                    operator: operator(pair.as_rule()).unwrap(),
+                    operator_span: self.span(&pair),
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+            })
+            .parse(expr.into_inner())
+    }
+}
+
+// Every expression with its 1-based line number, empty lines are skipped
+pub fn parse(
+    input: &str,
+    table: &OperatorTable,
+) -> Result<Vec<(usize, Expression)>, ExpressionError> {
+    let file = CalcParser::parse(Rule::file, input)
+        .map_err(|error| {
+            let (line, column) = match error.line_col {
+                LineColLocation::Pos(position) => position,
+                LineColLocation::Span(start, _) => start,
+            };
+
+            ExpressionError {
+                kind: ErrorKind::Syntax(error.variant.message().to_string()),
+                line,
+                column,
+            }
+        })?
+        .next()
+        .unwrap();
+    let pratt = pratt_parser(table);
+
+    file.into_inner()
+        .filter(|pair| pair.as_rule() == Rule::line)
+        .map(|line| {
+            let (number, _) = line.line_col();
+            // The pair starts after any leading whitespace
+            let offset = input[..line.as_span().start()]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            let builder = Builder {
+                table,
+                pratt: &pratt,
+                text: &input[offset..],
+                offset,
+            };
+            let expr = line.into_inner().next().unwrap();
+
+            builder
+                .check(&expr)
+                .map(|_| (number, builder.expression(expr)))
+                .map_err(|error| ExpressionError {
+                    line: number,
+                    ..error
+                })
+        })
+        .collect()
+}
+
+pub fn solve<N: Number>(input: &str, table: &OperatorTable) -> Result<N, ExpressionError> {
+    parse(input, table)?
+        .iter()
+        .try_fold(N::zero(), |sum, (line, expression)| {
+            let value = expression.evaluate().map_err(|error| ExpressionError {
+                line: *line,
+                ..error
+            })?;
+
+            sum.checked_add(&value).ok_or(ExpressionError {
+                kind: ErrorKind::Overflow,
+                line: *line,
+                column: 1,
+            })
+        })
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::expression;
+    use crate::number::BigInt;
+
     use super::*;
 
     #[test]
     fn test_eval_a() {
-        assert_eq!(solve("1 + 2 * 3 + 4 * 5 + 6\n", false), 71)
+        assert_eq!(
+            solve("1 + 2 * 3 + 4 * 5 + 6\n", &OperatorTable::flat()),
+            Ok(71)
+        )
     }
 
     #[test]
     fn test_eval_b() {
-        assert_eq!(solve("1 + (2 * 3) + (4 * (5 + 6))\n", false), 51)
+        assert_eq!(
+            solve("1 + (2 * 3) + (4 * (5 + 6))\n", &OperatorTable::flat()),
+            Ok(51)
+        )
     }
 
     #[test]
     fn test_eval_c() {
-        assert_eq!(solve("2 * 3 + (4 * 5)\n", false), 26)
+        assert_eq!(solve("2 * 3 + (4 * 5)\n", &OperatorTable::flat()), Ok(26))
     }
 
     #[test]
     fn test_eval_d() {
-        assert_eq!(solve("5 + (8 * 3 + 9 + 3 * 4 * 3)\n", false), 437)
+        assert_eq!(
+            solve("5 + (8 * 3 + 9 + 3 * 4 * 3)\n", &OperatorTable::flat()),
+            Ok(437)
+        )
     }
 
     #[test]
     fn test_eval_e() {
         assert_eq!(
-            solve("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))\n", false),
-            12240
+            solve(
+                "5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))\n",
+                &OperatorTable::flat()
+            ),
+            Ok(12240)
         )
     }
 
     #[test]
     fn test_eval_f() {
         assert_eq!(
-            solve("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2\n", false),
-            13632
+            solve(
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2\n",
+                &OperatorTable::flat()
+            ),
+            Ok(13632)
         )
     }
 
     #[test]
     fn test_eval2_a() {
-        assert_eq!(solve("1 + (2 * 3) + (4 * (5 + 6))\n", true), 51)
+        assert_eq!(
+            solve(
+                "1 + (2 * 3) + (4 * (5 + 6))\n",
+                &OperatorTable::addition_first()
+            ),
+            Ok(51)
+        )
     }
 
     #[test]
     fn test_eval2_b() {
-        assert_eq!(solve("2 * 3 + (4 * 5)\n", true), 46)
+        assert_eq!(
+            solve("2 * 3 + (4 * 5)\n", &OperatorTable::addition_first()),
+            Ok(46)
+        )
     }
 
     #[test]
     fn test_eval2_c() {
-        assert_eq!(solve("5 + (8 * 3 + 9 + 3 * 4 * 3)\n", true), 1445)
+        assert_eq!(
+            solve(
+                "5 + (8 * 3 + 9 + 3 * 4 * 3)\n",
+                &OperatorTable::addition_first()
+            ),
+            Ok(1445)
+        )
     }
 
     #[test]
     fn test_eval2_d() {
         assert_eq!(
-            solve("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))\n", true),
-            669060
+            solve(
+                "5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))\n",
+                &OperatorTable::addition_first()
+            ),
+            Ok(669060)
         )
     }
 
     #[test]
     fn test_eval2_e() {
         assert_eq!(
-            solve("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2\n", true,),
-            23340
+            solve(
+                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2\n",
+                &OperatorTable::addition_first()
+            ),
+            Ok(23340)
         )
     }
+
+    #[test]
+    fn test_operator_table() {
+        let table = OperatorTable::arithmetic();
+
+        assert_eq!(solve("-2 ^ 2 + 7 % 4\n8 / (3 - 1)\n", &table), Ok(3));
+        assert_eq!(
+            solve::<i64>("-2 ^ 2\n", &OperatorTable::flat())
+                .unwrap_err()
+                .to_string(),
+            "line 1, column 1: unexpected \"-\""
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let table = OperatorTable::arithmetic();
+
+        assert_eq!(
+            solve::<i64>("1 + 2\n(1 + 2\n", &table).unwrap_err().to_string(),
+            "line 2, column 7: expected add, subtract, multiply, divide, remainder, power, or close"
+        );
+        assert_eq!(
+            solve::<i64>("1 + 2\n4 / (2 - 2)\n", &table)
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 3: division by zero"
+        );
+    }
+
+    #[test]
+    fn test_multi_digit_and_whitespace() {
+        let table = OperatorTable::flat();
+
+        assert_eq!(solve("12*(3+4)\n", &table), Ok(84));
+        assert_eq!(solve("\t12\t* (3 +\t\t4)\r\n", &table), Ok(84));
+        assert_eq!(solve("12 \r* 7\r\n", &table), Ok(84));
+        assert!(solve::<i64>("12 *\u{a0}7\n", &table).is_err());
+        assert_eq!(
+            solve("100 + 23\n  45*2  \n", &OperatorTable::addition_first()),
+            Ok(213)
+        );
+    }
+
+    #[test]
+    fn test_empty_lines() {
+        let table = OperatorTable::flat();
+
+        assert_eq!(solve("1 + 2\n\n  \r\n3 * 4", &table), Ok(15));
+        assert_eq!(solve::<i64>("", &table), Ok(0));
+        assert_eq!(
+            solve::<u64>("1\n\n2 * (3 + 4\n", &table)
+                .unwrap_err()
+                .to_string(),
+            "line 3, column 11: expected add, subtract, multiply, divide, remainder, power, or close"
+        );
+        assert_eq!(
+            solve::<u64>("1\n\n2 % 3\n", &table)
+                .unwrap_err()
+                .to_string(),
+            "line 3, column 3: operator '%' is not in the operator table"
+        );
+    }
+
+    #[test]
+    fn test_same_tree_as_handwritten_parser() {
+        let tables = [
+            OperatorTable::flat(),
+            OperatorTable::addition_first(),
+            OperatorTable::arithmetic(),
+            OperatorTable::new()
+                .binary(BinaryOperator::Subtract, 255, Associativity::Left)
+                .binary(BinaryOperator::Power, 255, Associativity::Right)
+                .binary(BinaryOperator::Add, 0, Associativity::Left)
+                .negation(255),
+        ];
+        let inputs = [
+            "1 + 2 * 3 + 4 * 5 + 6",
+            "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+            "-2 ^ 2 + 7 % 4",
+            "2 ^ 3 ^ 2 - 1 - 1",
+            "--3 * -(1 + 1)",
+            "-10 - 4 + 1",
+            "\t12\t* (3 +\t\t4)",
+        ];
+
+        for table in tables.iter() {
+            for input in inputs.iter() {
+                assert_eq!(
+                    parse(input, table).map(|lines| lines[0].1.clone()).ok(),
+                    expression::parse(input, table).ok(),
+                    "{}",
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_numeric_types() {
+        let input = format!("{}\n", vec!["9"; 25].join(" * "));
+        let table = OperatorTable::flat();
+
+        assert!(solve::<u64>(&input, &table).is_err());
+        assert_eq!(
+            solve::<BigInt>(&input, &table).map(|value| value.to_string()),
+            Ok("717897987691852588770249".to_string())
+        );
+    }
 }
@@ -0,0 +1,221 @@
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::grammar::{Grammar, Symbol};
+
+// A partially matched alternative: `dot` symbols of it matched the message
+// from `origin` up to the set the item is in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Item {
+    pub(crate) rule: usize,
+    pub(crate) alternative: usize,
+    pub(crate) dot: usize,
+    pub(crate) origin: usize,
+}
+
+impl Item {
+    pub(crate) fn new(rule: usize, alternative: usize, origin: usize) -> Self {
+        Item {
+            rule,
+            alternative,
+            dot: 0,
+            origin,
+        }
+    }
+
+    fn advance(&self) -> Item {
+        Item {
+            dot: self.dot + 1,
+            ..*self
+        }
+    }
+}
+
+// One item set per position in the message, the last one is after its end.
+// Alternatives never match an empty string, so an item completed at a
+// position always started before it and the completion step only looks at
+// sets that are already final.
+#[derive(Debug, Clone)]
+pub(crate) struct Chart<'g> {
+    grammar: &'g Grammar,
+    pub(crate) start: usize,
+    pub(crate) message: Vec<char>,
+    pub(crate) sets: Vec<Vec<Item>>,
+    members: Vec<FxHashSet<Item>>,
+    // Items of each set by the rule after their dot, the parents a completed
+    // rule advances
+    waiting: Vec<FxHashMap<usize, Vec<Item>>>,
+}
+
+impl<'g> Chart<'g> {
+    pub(crate) fn new(grammar: &'g Grammar, start: usize, message: &str) -> Self {
+        let mut chart = Chart::empty(grammar, start);
+        chart.fill(message);
+
+        chart
+    }
+
+    pub(crate) fn empty(grammar: &'g Grammar, start: usize) -> Self {
+        Chart {
+            grammar,
+            start,
+            message: Vec::new(),
+            sets: Vec::new(),
+            members: Vec::new(),
+            waiting: Vec::new(),
+        }
+    }
+
+    // Replaces the chart with the one for `message`, keeping the buffers
+    pub(crate) fn fill(&mut self, message: &str) {
+        self.message.clear();
+        self.message.extend(message.chars());
+
+        let length = self.message.len() + 1;
+        self.sets.iter_mut().for_each(Vec::clear);
+        self.sets.resize_with(length, Vec::new);
+        self.members.iter_mut().for_each(FxHashSet::clear);
+        self.members.resize_with(length, FxHashSet::default);
+        self.waiting.iter_mut().for_each(FxHashMap::clear);
+        self.waiting.resize_with(length, FxHashMap::default);
+
+        for alternative in 0..self.grammar.alternatives(self.start).len() {
+            self.add(0, Item::new(self.start, alternative, 0));
+        }
+
+        for position in 0..length {
+            let mut index = 0;
+
+            while index < self.sets[position].len() {
+                let item = self.sets[position][index];
+                index += 1;
+
+                match self.next_symbol(&item) {
+                    Some(Symbol::Char(char)) => {
+                        if self.message.get(position) == Some(&char) {
+                            self.add(position + 1, item.advance());
+                        }
+                    }
+                    // Only the first item waiting for a rule predicts it
+                    Some(Symbol::Rule(rule)) if self.waiting[position][&rule][0] == item => {
+                        for alternative in 0..self.grammar.alternatives(rule).len() {
+                            self.add(position, Item::new(rule, alternative, position));
+                        }
+                    }
+                    Some(Symbol::Rule(_)) => {}
+                    None => {
+                        // The origin set is final, only this one grows
+                        let parents = self.waiting[item.origin]
+                            .get(&item.rule)
+                            .map_or(0, Vec::len);
+
+                        for parent in 0..parents {
+                            let parent = self.waiting[item.origin][&item.rule][parent];
+                            self.add(position, parent.advance());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn add(&mut self, position: usize, item: Item) {
+        if self.members[position].insert(item) {
+            self.sets[position].push(item);
+
+            if let Some(Symbol::Rule(rule)) = self.next_symbol(&item) {
+                self.waiting[position].entry(rule).or_default().push(item);
+            }
+        }
+    }
+
+    pub(crate) fn grammar(&self) -> &'g Grammar {
+        self.grammar
+    }
+
+    pub(crate) fn contains(&self, position: usize, item: &Item) -> bool {
+        self.members[position].contains(item)
+    }
+
+    pub(crate) fn symbols(&self, item: &Item) -> &'g [Symbol] {
+        &self.grammar.alternatives(item.rule)[item.alternative]
+    }
+
+    pub(crate) fn next_symbol(&self, item: &Item) -> Option<Symbol> {
+        self.symbols(item).get(item.dot).copied()
+    }
+
+    // Whether the start rule matches the first `position` characters
+    pub(crate) fn completes(&self, position: usize) -> bool {
+        self.sets[position].iter().any(|item| {
+            item.rule == self.start && item.origin == 0 && self.next_symbol(item).is_none()
+        })
+    }
+
+    pub(crate) fn accepts(&self) -> bool {
+        self.completes(self.message.len())
+    }
+}
+
+impl Grammar {
+    // Whether the whole message matches `start`, in at most cubic time
+    pub fn matches_rule(&self, start: usize, message: &str) -> bool {
+        self.rule(start).is_some() && Chart::new(self, start, message).accepts()
+    }
+
+    pub fn matches(&self, message: &str) -> bool {
+        self.matches_rule(0, message)
+    }
+
+    // How many messages match rule 0, sharing one chart between them
+    pub fn count_matches(&self, messages: &[&str]) -> usize {
+        if self.rule(0).is_none() {
+            return 0;
+        }
+
+        let mut chart = Chart::empty(self, 0);
+
+        messages
+            .iter()
+            .filter(|message| {
+                chart.fill(message);
+                chart.accepts()
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grammar::parse_input;
+
+    use super::*;
+
+    fn grammar(rules: &[&str]) -> Grammar {
+        parse_input(rules).unwrap().0
+    }
+
+    #[test]
+    fn test_many_alternatives() {
+        let grammar = grammar(&["0: 1 | 2 | 1 2 1", "1: \"a\"", "2: \"b\""]);
+
+        assert!(grammar.matches("a"));
+        assert!(grammar.matches("b"));
+        assert!(grammar.matches("aba"));
+        assert!(!grammar.matches("ab"));
+        assert!(!grammar.matches(""));
+        assert_eq!(grammar.count_matches(&["aba", "ab", "b", "", "a"]), 3);
+    }
+
+    #[test]
+    fn test_recursive_rules() {
+        // a^n b^n, and a left recursive list of a
+        let grammar = grammar(&["0: 1 2 | 1 0 2", "1: \"a\"", "2: \"b\"", "3: 1 | 3 1"]);
+
+        assert!(grammar.matches("aaabbb"));
+        assert!(!grammar.matches("aaabb"));
+        assert!(!grammar.matches("abab"));
+        assert!(grammar.matches_rule(3, &"a".repeat(50)));
+        assert!(!grammar.matches_rule(3, "aab"));
+        assert!(!grammar.matches_rule(4, "a"));
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use fxhash::FxHashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    Char(char),
+    Rule(usize),
+}
+
+// The right hand side of a rule, `"a"` or any number of alternatives like
+// `1 2 | 3 | 4 5 6`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    Char(char),
+    Alternatives(Vec<Vec<usize>>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    MissingColon,
+    InvalidIndex(String),
+    InvalidChar(String),
+    EmptyAlternative,
+}
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::MissingColon => write!(f, "expected \"<index>: <rule>\""),
+            RuleError::InvalidIndex(index) => write!(f, "invalid rule number \"{}\"", index),
+            RuleError::InvalidChar(char) => {
+                write!(f, "invalid character {} (expected \"x\")", char)
+            }
+            RuleError::EmptyAlternative => write!(f, "empty alternative"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+fn parse_index(input: &str) -> Result<usize, RuleError> {
+    input
+        .parse()
+        .map_err(|_| RuleError::InvalidIndex(input.to_string()))
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(input: &str) -> Result<Rule, Self::Err> {
+        let input = input.trim();
+
+        if input.starts_with('"') {
+            let mut chars = input.chars();
+
+            return match (chars.next(), chars.next(), chars.next(), chars.next()) {
+                (Some('"'), Some(char), Some('"'), None) => Ok(Rule::Char(char)),
+                _ => Err(RuleError::InvalidChar(input.to_string())),
+            };
+        }
+
+        input
+            .split('|')
+            .map(|alternative| {
+                let numbers = alternative
+                    .split_whitespace()
+                    .map(parse_index)
+                    .collect::<Result<Vec<usize>, _>>()?;
+
+                if numbers.is_empty() {
+                    Err(RuleError::EmptyAlternative)
+                } else {
+                    Ok(numbers)
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(Rule::Alternatives)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Char(char) => write!(f, "\"{}\"", char),
+            Rule::Alternatives(alternatives) => {
+                let alternatives: Vec<String> = alternatives
+                    .iter()
+                    .map(|numbers| {
+                        numbers
+                            .iter()
+                            .map(|number| number.to_string())
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    })
+                    .collect();
+
+                write!(f, "{}", alternatives.join(" | "))
+            }
+        }
+    }
+}
+
+// One line of the rule list, `8: 42 | 42 8`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub index: usize,
+    pub rule: Rule,
+}
+
+impl FromStr for Definition {
+    type Err = RuleError;
+
+    fn from_str(input: &str) -> Result<Definition, Self::Err> {
+        let (index, rule) = input.split_once(':').ok_or(RuleError::MissingColon)?;
+
+        Ok(Definition {
+            index: parse_index(index.trim())?,
+            rule: rule.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrammarError {
+    Rule { line: String, error: RuleError },
+    Duplicate(usize),
+    Undefined { rule: usize, reference: usize },
+}
+
+impl Display for GrammarError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarError::Rule { line, error } => {
+                write!(f, "cannot parse \"{}\": {}", line, error)
+            }
+            GrammarError::Duplicate(index) => write!(f, "rule {} is defined twice", index),
+            GrammarError::Undefined { rule, reference } => {
+                write!(f, "rule {} refers to undefined rule {}", rule, reference)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GrammarError {}
+
+// A context-free grammar where every rule references only defined rules and
+// every alternative matches at least one character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    rules: HashMap<usize, Rule>,
+    productions: FxHashMap<usize, Vec<Vec<Symbol>>>,
+}
+
+impl Grammar {
+    pub fn new(definitions: Vec<Definition>) -> Result<Grammar, GrammarError> {
+        let mut rules = HashMap::new();
+
+        for definition in definitions {
+            if rules.insert(definition.index, definition.rule).is_some() {
+                return Err(GrammarError::Duplicate(definition.index));
+            }
+        }
+
+        Grammar::compile(rules)
+    }
+
+    fn compile(rules: HashMap<usize, Rule>) -> Result<Grammar, GrammarError> {
+        let mut productions = FxHashMap::default();
+
+        for (&index, rule) in rules.iter() {
+            let alternatives = match rule {
+                Rule::Char(char) => vec![vec![Symbol::Char(*char)]],
+                Rule::Alternatives(alternatives) => alternatives
+                    .iter()
+                    .map(|numbers| {
+                        numbers
+                            .iter()
+                            .map(|&reference| {
+                                if rules.contains_key(&reference) {
+                                    Ok(Symbol::Rule(reference))
+                                } else {
+                                    Err(GrammarError::Undefined {
+                                        rule: index,
+                                        reference,
+                                    })
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect::<Result<_, _>>()?,
+            };
+
+            productions.insert(index, alternatives);
+        }
+
+        Ok(Grammar { rules, productions })
+    }
+
+    pub fn rules(&self) -> &HashMap<usize, Rule> {
+        &self.rules
+    }
+
+    pub fn rule(&self, index: usize) -> Option<&Rule> {
+        self.rules.get(&index)
+    }
+
+    // Adds or replaces one rule, like the loops of part 2
+    pub fn with_rule(mut self, definition: Definition) -> Result<Grammar, GrammarError> {
+        self.rules.insert(definition.index, definition.rule);
+
+        Grammar::compile(self.rules)
+    }
+
+    pub(crate) fn alternatives(&self, index: usize) -> &[Vec<Symbol>] {
+        &self.productions[&index]
+    }
+}
+
+// Rule lines contain a colon, every other line is a message
+pub fn parse_input<'a>(input: &[&'a str]) -> Result<(Grammar, Vec<&'a str>), GrammarError> {
+    let (definitions, messages): (Vec<&str>, Vec<&str>) =
+        input.iter().partition(|line| line.contains(':'));
+
+    let definitions = definitions
+        .iter()
+        .map(|line| {
+            line.parse().map_err(|error| GrammarError::Rule {
+                line: line.to_string(),
+                error,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((Grammar::new(definitions)?, messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule() {
+        assert_eq!("\"a\"".parse(), Ok(Rule::Char('a')));
+        assert_eq!(
+            "1 2 | 3 | 4 5 6".parse(),
+            Ok(Rule::Alternatives(vec![vec![1, 2], vec![3], vec![4, 5, 6]]))
+        );
+        assert_eq!("1 | ".parse::<Rule>(), Err(RuleError::EmptyAlternative));
+        assert_eq!(
+            "1 x".parse::<Rule>(),
+            Err(RuleError::InvalidIndex("x".to_string()))
+        );
+        assert_eq!(
+            "\"ab\"".parse::<Rule>(),
+            Err(RuleError::InvalidChar("\"ab\"".to_string()))
+        );
+        assert_eq!(
+            "42 | 42 8".parse::<Rule>().unwrap().to_string(),
+            "42 | 42 8"
+        );
+    }
+
+    #[test]
+    fn test_grammar_errors() {
+        assert_eq!(
+            parse_input(&["0: 1 2", "1: \"a\""])
+                .unwrap_err()
+                .to_string(),
+            "rule 0 refers to undefined rule 2"
+        );
+        assert_eq!(
+            parse_input(&["0: 1", "0: \"a\""]).unwrap_err(),
+            GrammarError::Duplicate(0)
+        );
+        assert_eq!(
+            parse_input(&["0 1", "x: 1"]).unwrap_err().to_string(),
+            "cannot parse \"x: 1\": invalid rule number \"x\""
+        );
+    }
+
+    #[test]
+    fn test_with_rule() {
+        let (grammar, messages) = parse_input(&["0: 1", "1: \"a\"", "aa"]).unwrap();
+
+        assert_eq!(messages, vec!["aa"]);
+        assert_eq!(
+            grammar
+                .clone()
+                .with_rule("0: 1 | 1 0".parse().unwrap())
+                .unwrap()
+                .rule(0),
+            Some(&Rule::Alternatives(vec![vec![1], vec![1, 0]]))
+        );
+        assert_eq!(
+            grammar.with_rule("0: 2".parse().unwrap()),
+            Err(GrammarError::Undefined {
+                rule: 0,
+                reference: 2
+            })
+        );
+    }
+}
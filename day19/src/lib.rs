@@ -1,138 +1,24 @@
-use std::collections::{HashMap, VecDeque};
+pub mod earley;
+pub mod grammar;
+pub mod pattern;
+pub mod tree;
 
-use lazy_static::lazy_static;
-use regex::{Match, Regex};
-
-#[derive(Debug)]
-struct Rule {
-    char: Option<char>,
-    left_numbers: Option<Vec<usize>>,
-    right_numbers: Option<Vec<usize>>,
-}
-
-fn is_valid(rules: &HashMap<usize, Rule>, message: &str, next_rules: VecDeque<&Rule>) -> bool {
-    if message.len() == 0 {
-        return next_rules.len() == 0;
-    }
-
-    let mut next_rules = next_rules.clone();
-
-    let rule = match next_rules.pop_front() {
-        None => return false,
-        Some(rule) => rule,
-    };
-
-    // test char
-    match rule.char {
-        Some(rule_char) => {
-            let message_char = message.chars().next().unwrap();
-
-            return if rule_char == message_char {
-                is_valid(rules, &message[1..], next_rules)
-            } else {
-                false
-            };
-        }
-        None => {}
-    }
-
-    // test left side
-    let left_numbers = rule.left_numbers.as_ref().unwrap();
-    let mut left_next_rules = VecDeque::new();
-
-    left_numbers
-        .iter()
-        .for_each(|number| left_next_rules.push_back(rules.get(number).unwrap()));
-
-    left_next_rules.append(&mut next_rules.clone());
-
-    if is_valid(rules, message, left_next_rules) {
-        return true;
-    }
-
-    // test right side
-    if rule.right_numbers.is_some() {
-        let right_numbers = rule.right_numbers.as_ref().unwrap();
-        let mut right_next_rules = VecDeque::new();
-
-        right_numbers
-            .iter()
-            .for_each(|number| right_next_rules.push_back(rules.get(number).unwrap()));
-
-        right_next_rules.append(&mut next_rules.clone());
-
-        if is_valid(rules, message, right_next_rules) {
-            return true;
-        }
-    }
-
-    false
-}
-
-fn parse_char(char: Option<Match>) -> Option<char> {
-    if char.is_some() {
-        Some(char.unwrap().as_str()[1..2].parse().unwrap())
-    } else {
-        None
-    }
-}
-
-fn parse_numbers(numbers: Option<Match>) -> Option<Vec<usize>> {
-    if numbers.is_some() {
-        Some(
-            numbers
-                .unwrap()
-                .as_str()
-                .split(" ")
-                .map(|number| number.parse::<usize>().unwrap())
-                .collect(),
-        )
-    } else {
-        None
-    }
-}
+use grammar::parse_input;
 
 pub fn part1(input: &Vec<&str>) -> usize {
-    lazy_static! {
-        static ref REGEX: Regex =
-            Regex::new(r#"^(?P<index>\d+): (?:(?P<char>"[a-b]")|(:?(?P<left_numbers>(\d+ ?)+)(:? \| (?P<right_numbers>(\d+ ?)+))?))$"#)
-                .unwrap();
-    }
-
-    let mut rules = HashMap::new();
-    let mut messages = Vec::new();
-
-    input.iter().for_each(|line| match REGEX.captures(line) {
-        Some(captures) => {
-            let index: usize = captures.name("index").unwrap().as_str().parse().unwrap();
-            let char = parse_char(captures.name("char"));
-            let left_numbers = parse_numbers(captures.name("left_numbers"));
-            let right_numbers = parse_numbers(captures.name("right_numbers"));
-
-            rules.insert(
-                index,
-                Rule {
-                    char,
-                    left_numbers,
-                    right_numbers,
-                },
-            );
-        }
-        None => messages.push(line),
-    });
+    let (grammar, messages) = parse_input(input).unwrap_or_else(|error| panic!("{}", error));
 
-    messages
-        .iter()
-        .filter(|message| is_valid(&rules, message, VecDeque::from(vec![&rules[&0]])))
-        .count()
+    grammar.count_matches(&messages)
 }
 
 pub fn part2(input: &Vec<&str>) -> usize {
-    let mut input = input.clone();
-    input.push("8: 42 | 42 8");
-    input.push("11: 42 31 | 42 11 31");
+    let (grammar, messages) = parse_input(input).unwrap_or_else(|error| panic!("{}", error));
+    let grammar = grammar
+        .with_rule("8: 42 | 42 8".parse().unwrap())
+        .and_then(|grammar| grammar.with_rule("11: 42 31 | 42 11 31".parse().unwrap()))
+        .unwrap_or_else(|error| panic!("{}", error));
 
-    part1(&input)
+    grammar.count_matches(&messages)
 }
 
 #[cfg(test)]
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use regex::Regex;
+
+use crate::grammar::Rule;
+
+// How to deal with rules that refer back to themselves
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Expansion {
+    // A rule may only repeat itself at one end of a single alternative, like
+    // `8: 42 | 42 8`, any other cycle is an error
+    Strict,
+    // A rule is expanded at most this many times inside itself, the pattern
+    // then only matches messages that need no deeper recursion
+    Bounded(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternError {
+    Undefined(usize),
+    // The rules of a cycle that is not a repetition, in the order they refer
+    // to each other
+    Cycle(Vec<usize>),
+    // Every alternative needs more recursion than allowed
+    Empty(usize),
+    // Usually a bounded expansion past the size limit of `regex`
+    Compile(String),
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::Undefined(rule) => write!(f, "rule {} is not defined", rule),
+            PatternError::Cycle(rules) => {
+                let rules: Vec<String> = rules.iter().map(|rule| rule.to_string()).collect();
+
+                write!(
+                    f,
+                    "the rules contain a cycle that is not a repetition: {}",
+                    rules.join(" -> ")
+                )
+            }
+            PatternError::Empty(rule) => {
+                write!(
+                    f,
+                    "rule {} matches nothing within the expansion depth",
+                    rule
+                )
+            }
+            PatternError::Compile(error) => write!(f, "cannot compile the pattern: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+// The alternative of `index` that repeats the others, either `B | P index`
+// or `B | index P`. It must be the only one referring to the rule, and only
+// once.
+fn repetition(index: usize, alternatives: &[Vec<usize>]) -> Option<(usize, Side)> {
+    let mut recursive = alternatives
+        .iter()
+        .enumerate()
+        .filter(|(_, numbers)| numbers.contains(&index));
+    let (position, numbers) = recursive.next()?;
+
+    if recursive.next().is_some()
+        || alternatives.len() < 2
+        || numbers.len() < 2
+        || 1 < numbers.iter().filter(|&&number| number == index).count()
+    {
+        return None;
+    }
+
+    if numbers.last() == Some(&index) {
+        Some((position, Side::Right))
+    } else if numbers.first() == Some(&index) {
+        Some((position, Side::Left))
+    } else {
+        None
+    }
+}
+
+struct Expander<'a> {
+    rules: &'a HashMap<usize, Rule>,
+    expansion: Expansion,
+    stack: Vec<usize>,
+    // Only used for strict expansion, where a rule always gives the same pattern
+    done: HashMap<usize, String>,
+}
+
+impl<'a> Expander<'a> {
+    // `None` when the rule cannot be expanded within the depth
+    fn expand(&mut self, index: usize) -> Result<Option<String>, PatternError> {
+        if let Some(pattern) = self.done.get(&index) {
+            return Ok(Some(pattern.clone()));
+        }
+
+        let rule = self
+            .rules
+            .get(&index)
+            .ok_or(PatternError::Undefined(index))?;
+        let depth = self.stack.iter().filter(|&&rule| rule == index).count();
+
+        match self.expansion {
+            Expansion::Strict if 0 < depth => {
+                let first = self.stack.iter().position(|&rule| rule == index).unwrap();
+                let mut cycle = self.stack[first..].to_vec();
+                cycle.push(index);
+
+                return Err(PatternError::Cycle(cycle));
+            }
+            Expansion::Bounded(limit) if limit < depth => return Ok(None),
+            _ => {}
+        }
+
+        let alternatives = match rule {
+            Rule::Char(char) => return Ok(Some(regex::escape(&char.to_string()))),
+            Rule::Alternatives(alternatives) => alternatives,
+        };
+
+        self.stack.push(index);
+        let pattern = self.alternatives(index, alternatives);
+        self.stack.pop();
+
+        let pattern = match pattern? {
+            Some(pattern) => pattern,
+            None => return Ok(None),
+        };
+
+        if self.expansion == Expansion::Strict {
+            self.done.insert(index, pattern.clone());
+        }
+
+        Ok(Some(pattern))
+    }
+
+    // `None` when any rule of the sequence cannot be expanded
+    fn sequence(&mut self, numbers: &[usize]) -> Result<Option<String>, PatternError> {
+        let mut sequence = String::new();
+
+        for &number in numbers.iter() {
+            match self.expand(number)? {
+                Some(pattern) => sequence.push_str(&pattern),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(sequence))
+    }
+
+    fn alternatives(
+        &mut self,
+        index: usize,
+        alternatives: &[Vec<usize>],
+    ) -> Result<Option<String>, PatternError> {
+        let repetition = match self.expansion {
+            Expansion::Strict => repetition(index, alternatives),
+            Expansion::Bounded(_) => None,
+        };
+        let mut patterns = Vec::new();
+
+        for (position, numbers) in alternatives.iter().enumerate() {
+            if repetition.is_some_and(|(repeated, _)| repeated == position) {
+                continue;
+            }
+
+            patterns.extend(self.sequence(numbers)?);
+        }
+
+        let base = match patterns.len() {
+            0 => return Ok(None),
+            1 => patterns.pop().unwrap(),
+            _ => format!("(?:{})", patterns.join("|")),
+        };
+
+        let (position, side) = match repetition {
+            Some(repetition) => repetition,
+            None => return Ok(Some(base)),
+        };
+        let numbers = &alternatives[position];
+        let repeated = match side {
+            Side::Left => &numbers[1..],
+            Side::Right => &numbers[..numbers.len() - 1],
+        };
+        let repeated = self
+            .sequence(repeated)?
+            .expect("Something went wrong expanding a repetition!");
+
+        Ok(Some(match side {
+            _ if repeated == base => format!("(?:{})+", base),
+            Side::Left => format!("{}(?:{})*", base, repeated),
+            Side::Right => format!("(?:{})*{}", repeated, base),
+        }))
+    }
+}
+
+// An anchored pattern matching exactly the messages of rule `start`
+pub fn pattern(
+    rules: &HashMap<usize, Rule>,
+    start: usize,
+    expansion: Expansion,
+) -> Result<String, PatternError> {
+    let mut expander = Expander {
+        rules,
+        expansion,
+        stack: Vec::new(),
+        done: HashMap::new(),
+    };
+
+    let pattern = expander.expand(start)?.ok_or(PatternError::Empty(start))?;
+
+    Ok(format!("^{}$", pattern))
+}
+
+pub fn regex(
+    rules: &HashMap<usize, Rule>,
+    start: usize,
+    expansion: Expansion,
+) -> Result<Regex, PatternError> {
+    let pattern = pattern(rules, start, expansion)?;
+
+    Regex::new(&pattern).map_err(|error| PatternError::Compile(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grammar::{parse_input, Grammar};
+
+    use super::*;
+
+    fn grammar(rules: &[&str]) -> Grammar {
+        parse_input(rules).unwrap().0
+    }
+
+    #[test]
+    fn test_pattern() {
+        let grammar = grammar(&[
+            "0: 4 1 5",
+            "1: 2 3 | 3 2",
+            "2: 4 4 | 5 5",
+            "3: 4 5 | 5 4",
+            "4: \"a\"",
+            "5: \"b\"",
+        ]);
+
+        assert_eq!(
+            pattern(grammar.rules(), 0, Expansion::Strict),
+            Ok("^a(?:(?:aa|bb)(?:ab|ba)|(?:ab|ba)(?:aa|bb))b$".to_string())
+        );
+        assert_eq!(
+            pattern(grammar.rules(), 2, Expansion::Strict),
+            Ok("^(?:aa|bb)$".to_string())
+        );
+
+        let regex = regex(grammar.rules(), 0, Expansion::Strict).unwrap();
+
+        for message in ["ababbb", "bababa", "abbbab", "aaabbb", "aaaabbb"].iter() {
+            assert_eq!(regex.is_match(message), grammar.matches(message));
+        }
+    }
+
+    #[test]
+    fn test_special_characters() {
+        let grammar = grammar(&["0: 1 2", "1: \".\"", "2: \"+\""]);
+
+        assert_eq!(
+            pattern(grammar.rules(), 0, Expansion::Strict),
+            Ok("^\\.\\+$".to_string())
+        );
+    }
+
+    #[test]
+    fn test_recursive_rules() {
+        let grammar = grammar(&[
+            "0: 8 11",
+            "8: 1 | 1 8",
+            "11: 1 2 | 1 11 2",
+            "1: \"a\"",
+            "2: \"b\"",
+        ]);
+
+        assert_eq!(
+            pattern(grammar.rules(), 0, Expansion::Strict)
+                .unwrap_err()
+                .to_string(),
+            "the rules contain a cycle that is not a repetition: 11 -> 11"
+        );
+        assert_eq!(
+            pattern(grammar.rules(), 11, Expansion::Bounded(1)),
+            Ok("^(?:ab|aabb)$".to_string())
+        );
+
+        let regex = regex(grammar.rules(), 0, Expansion::Bounded(3)).unwrap();
+
+        for message in ["ab", "aab", "aaaabb", "aaaaabbbb", "aaaaaabbbbb", "abb"].iter() {
+            assert_eq!(
+                regex.is_match(message),
+                grammar.matches(message) && message.len() <= 9,
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_repetitions() {
+        let grammar = grammar(&[
+            "0: 8 9",
+            "8: 1 | 1 8",
+            "9: 2 | 9 1 2",
+            "10: 1 2 | 2 10",
+            "1: \"a\"",
+            "2: \"b\"",
+        ]);
+
+        assert_eq!(
+            pattern(grammar.rules(), 8, Expansion::Strict),
+            Ok("^(?:a)+$".to_string())
+        );
+        assert_eq!(
+            pattern(grammar.rules(), 9, Expansion::Strict),
+            Ok("^b(?:ab)*$".to_string())
+        );
+        assert_eq!(
+            pattern(grammar.rules(), 10, Expansion::Strict),
+            Ok("^(?:b)*ab$".to_string())
+        );
+
+        let regex = regex(grammar.rules(), 0, Expansion::Strict).unwrap();
+
+        for message in ["ab", "aaabab", "aabaab", "b", "abb", "aaababab"].iter() {
+            assert_eq!(
+                regex.is_match(message),
+                grammar.matches(message),
+                "{}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let grammar = grammar(&["0: 1 | 0 1", "1: \"a\"", "2: 2 1 | 3 2", "3: 2"]);
+
+        assert_eq!(
+            pattern(grammar.rules(), 4, Expansion::Strict),
+            Err(PatternError::Undefined(4))
+        );
+        assert_eq!(
+            pattern(grammar.rules(), 2, Expansion::Strict),
+            Err(PatternError::Cycle(vec![2, 2]))
+        );
+        assert_eq!(
+            pattern(grammar.rules(), 3, Expansion::Bounded(2)),
+            Err(PatternError::Empty(3))
+        );
+        assert!(pattern(grammar.rules(), 0, Expansion::Bounded(2)).is_ok());
+    }
+}
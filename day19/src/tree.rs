@@ -0,0 +1,330 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{self, Display, Formatter};
+
+use crate::earley::{Chart, Item};
+use crate::grammar::{Grammar, Rule, Symbol};
+
+// How a message was derived, every rule node records the alternative it used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tree {
+    Char(char),
+    Rule {
+        index: usize,
+        alternative: usize,
+        children: Vec<Tree>,
+    },
+}
+
+impl Tree {
+    // The characters the tree matched
+    pub fn text(&self) -> String {
+        match self {
+            Tree::Char(char) => char.to_string(),
+            Tree::Rule { children, .. } => children.iter().map(Tree::text).collect(),
+        }
+    }
+
+    fn render(&self, grammar: &Grammar, depth: usize, output: &mut String) {
+        if let Tree::Rule {
+            index,
+            alternative,
+            children,
+        } = self
+        {
+            let used = match grammar.rule(*index) {
+                Some(Rule::Alternatives(alternatives)) => alternatives[*alternative]
+                    .iter()
+                    .map(|number| number.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                Some(rule) => rule.to_string(),
+                None => String::new(),
+            };
+
+            output.push_str(&format!("{}{}: {}\n", "  ".repeat(depth), index, used));
+
+            for child in children {
+                child.render(grammar, depth + 1, output);
+            }
+        }
+    }
+
+    // One line per rule, indented by depth, with the alternative it used:
+    //
+    // 0: 4 1 5
+    //   4: "a"
+    //   1: 3 2
+    pub fn render_with(&self, grammar: &Grammar) -> String {
+        let mut output = String::new();
+        self.render(grammar, 0, &mut output);
+
+        output
+    }
+}
+
+// Why a message does not match: the longest prefix that could still be the
+// start of a match, and what could have come after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub prefix: String,
+    pub found: Option<char>,
+    pub expected_chars: Vec<char>,
+    pub expected_rules: Vec<usize>,
+    pub expected_end: bool,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut expected: Vec<String> = self
+            .expected_chars
+            .iter()
+            .map(|char| format!("'{}'", char))
+            .collect();
+
+        if self.expected_end {
+            expected.push("end of message".to_string());
+        }
+
+        let expected = match expected.len() {
+            0 => "nothing".to_string(),
+            _ => expected.join(" or "),
+        };
+        let found = match self.found {
+            Some(char) => format!("'{}'", char),
+            None => "end of message".to_string(),
+        };
+
+        write!(
+            f,
+            "after \"{}\": expected {}, found {}",
+            self.prefix, expected, found
+        )?;
+
+        if !self.expected_rules.is_empty() {
+            let rules: Vec<String> = self
+                .expected_rules
+                .iter()
+                .map(|rule| rule.to_string())
+                .collect();
+
+            write!(f, " (expecting rules {})", rules.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+type Path = HashSet<(usize, usize, usize)>;
+
+impl<'g> Chart<'g> {
+    // A derivation of `rule` over `start..end`. The path guards against rules
+    // like `3: 1 | 3` that derive themselves over the same span.
+    fn tree(&self, rule: usize, start: usize, end: usize, path: &mut Path) -> Option<Tree> {
+        if !path.insert((rule, start, end)) {
+            return None;
+        }
+
+        let alternatives = self.grammar().alternatives(rule).len();
+        let tree = (0..alternatives).find_map(|alternative| {
+            let mut complete = Item::new(rule, alternative, start);
+            complete.dot = self.symbols(&complete).len();
+
+            if !self.contains(end, &complete) {
+                return None;
+            }
+
+            self.children(&complete, start, end, path)
+                .map(|children| Tree::Rule {
+                    index: rule,
+                    alternative,
+                    children,
+                })
+        });
+
+        path.remove(&(rule, start, end));
+        tree
+    }
+
+    // Subtrees for the symbols before the dot of `item` over `start..end`
+    fn children(
+        &self,
+        item: &Item,
+        start: usize,
+        end: usize,
+        path: &mut Path,
+    ) -> Option<Vec<Tree>> {
+        if item.dot == 0 {
+            return if start == end { Some(Vec::new()) } else { None };
+        }
+
+        let previous = Item {
+            dot: item.dot - 1,
+            ..*item
+        };
+
+        match self.symbols(item)[previous.dot] {
+            Symbol::Char(char) => {
+                if start < end && self.message[end - 1] == char && self.contains(end - 1, &previous)
+                {
+                    let mut children = self.children(&previous, start, end - 1, path)?;
+                    children.push(Tree::Char(char));
+
+                    return Some(children);
+                }
+
+                None
+            }
+            Symbol::Rule(rule) => (start..end).rev().find_map(|middle| {
+                if !self.contains(middle, &previous) {
+                    return None;
+                }
+
+                let child = self.tree(rule, middle, end, path)?;
+                let mut children = self.children(&previous, start, middle, path)?;
+                children.push(child);
+
+                Some(children)
+            }),
+        }
+    }
+
+    fn mismatch(&self) -> Mismatch {
+        let position = (0..self.sets.len())
+            .rev()
+            .find(|&position| !self.sets[position].is_empty())
+            .unwrap_or(0);
+
+        let mut expected_chars = BTreeSet::new();
+        let mut expected_rules = BTreeSet::new();
+
+        for item in self.sets[position].iter() {
+            match self.next_symbol(item) {
+                Some(Symbol::Char(char)) => {
+                    expected_chars.insert(char);
+                }
+                Some(Symbol::Rule(rule)) => {
+                    expected_rules.insert(rule);
+                }
+                None => {}
+            }
+        }
+
+        Mismatch {
+            prefix: self.message[..position].iter().collect(),
+            found: self.message.get(position).copied(),
+            expected_chars: expected_chars.into_iter().collect(),
+            expected_rules: expected_rules.into_iter().collect(),
+            expected_end: self.completes(position),
+        }
+    }
+}
+
+impl Grammar {
+    // The derivation tree of a matching message, or why it does not match
+    pub fn parse_rule(&self, start: usize, message: &str) -> Result<Tree, Mismatch> {
+        if self.rule(start).is_none() {
+            return Err(Mismatch {
+                prefix: String::new(),
+                found: message.chars().next(),
+                expected_chars: Vec::new(),
+                expected_rules: Vec::new(),
+                expected_end: false,
+            });
+        }
+
+        let chart = Chart::new(self, start, message);
+
+        if !chart.accepts() {
+            return Err(chart.mismatch());
+        }
+
+        let tree = chart.tree(start, 0, chart.message.len(), &mut HashSet::new());
+
+        Ok(tree.expect("Something went wrong building the parse tree!"))
+    }
+
+    pub fn parse(&self, message: &str) -> Result<Tree, Mismatch> {
+        self.parse_rule(0, message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grammar::parse_input;
+
+    const RULES: [&str; 6] = [
+        "0: 4 1 5",
+        "1: 2 3 | 3 2",
+        "2: 4 4 | 5 5",
+        "3: 4 5 | 5 4",
+        "4: \"a\"",
+        "5: \"b\"",
+    ];
+
+    #[test]
+    fn test_render_tree() {
+        let (grammar, _) = parse_input(&RULES).unwrap();
+        let tree = grammar.parse("ababbb").unwrap();
+
+        assert_eq!(tree.text(), "ababbb");
+        assert_eq!(
+            tree.render_with(&grammar),
+            "0: 4 1 5
+  4: \"a\"
+  1: 3 2
+    3: 5 4
+      5: \"b\"
+      4: \"a\"
+    2: 5 5
+      5: \"b\"
+      5: \"b\"
+  5: \"b\"
+"
+        );
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let (grammar, _) = parse_input(&RULES).unwrap();
+
+        let mismatch = grammar.parse("bababa").unwrap_err();
+        assert_eq!(mismatch.prefix, "");
+        assert_eq!(mismatch.expected_rules, vec![4]);
+        assert_eq!(
+            mismatch.to_string(),
+            "after \"\": expected 'a', found 'b' (expecting rules 4)"
+        );
+
+        assert_eq!(
+            grammar.parse("aaab").unwrap_err().to_string(),
+            "after \"aaab\": expected 'a', found end of message (expecting rules 4)"
+        );
+        assert_eq!(
+            grammar.parse("aaaabbb").unwrap_err().to_string(),
+            "after \"aaaabb\": expected end of message, found 'b'"
+        );
+    }
+
+    #[test]
+    fn test_recursive_tree() {
+        let (grammar, _) = parse_input(&[
+            "0: 8 11",
+            "8: 1 | 1 8",
+            "11: 1 2 | 1 11 2",
+            "1: \"a\"",
+            "2: \"b\"",
+            "3: 1 | 3",
+        ])
+        .unwrap();
+
+        let tree = grammar.parse("aaaabb").unwrap();
+        assert_eq!(tree.text(), "aaaabb");
+        assert_eq!(
+            grammar.parse("aaabbb").unwrap_err().to_string(),
+            "after \"aaabb\": expected end of message, found 'b'"
+        );
+
+        // Rule 3 derives itself over the same span
+        assert_eq!(grammar.parse_rule(3, "a").unwrap().text(), "a");
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use crate::Tile;
+
+pub type Edge = Vec<bool>;
+
+// The same for an edge and its reverse, so it does not depend on how the tile
+// is rotated or flipped
+pub fn signature(edge: &[bool]) -> Edge {
+    let reversed: Edge = edge.iter().rev().copied().collect();
+
+    if reversed.as_slice() < edge {
+        reversed
+    } else {
+        edge.to_vec()
+    }
+}
+
+// The tiles sharing each edge signature. Tiles are referred to by their
+// position in the slice the index was built from.
+#[derive(Debug)]
+pub struct EdgeIndex {
+    tiles: HashMap<Edge, Vec<usize>>,
+}
+
+impl EdgeIndex {
+    pub fn new(tiles: &[Tile]) -> Self {
+        let mut index: HashMap<Edge, Vec<usize>> = HashMap::new();
+
+        for (position, tile) in tiles.iter().enumerate() {
+            for edge in tile.edges().iter() {
+                index.entry(signature(edge)).or_default().push(position);
+            }
+        }
+
+        EdgeIndex { tiles: index }
+    }
+
+    // Other tiles with an edge that fits `edge`
+    pub fn matching(&self, edge: &[bool], tile: usize) -> Vec<usize> {
+        self.tiles
+            .get(&signature(edge))
+            .map(|tiles| {
+                tiles
+                    .iter()
+                    .copied()
+                    .filter(|&other| other != tile)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn is_border(&self, edge: &[bool], tile: usize) -> bool {
+        self.matching(edge, tile).is_empty()
+    }
+
+    // Top, right, bottom and left, whether they match no other tile
+    fn borders(&self, tiles: &[Tile], tile: usize) -> Vec<bool> {
+        tiles[tile]
+            .edges()
+            .iter()
+            .map(|edge| self.is_border(edge, tile))
+            .collect()
+    }
+
+    // How many edges of the tile match no other tile
+    pub fn border_edges(&self, tiles: &[Tile], tile: usize) -> usize {
+        self.borders(tiles, tile)
+            .iter()
+            .filter(|&&border| border)
+            .count()
+    }
+
+    // Every tile sharing an edge with `tile`
+    pub fn neighbours(&self, tiles: &[Tile], tile: usize) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = tiles[tile]
+            .edges()
+            .iter()
+            .flat_map(|edge| self.matching(edge, tile))
+            .collect();
+
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    // Tiles with two unmatched edges next to each other
+    pub fn corners(&self, tiles: &[Tile]) -> Vec<usize> {
+        (0..tiles.len())
+            .filter(|&tile| {
+                let borders = self.borders(tiles, tile);
+
+                (0..4).any(|side| borders[side] && borders[(side + 1) % 4])
+            })
+            .collect()
+    }
+
+    // Tiles on the border of the image that are not corners
+    pub fn sides(&self, tiles: &[Tile]) -> Vec<usize> {
+        let corners = self.corners(tiles);
+
+        (0..tiles.len())
+            .filter(|&tile| self.border_edges(tiles, tile) > 0 && !corners.contains(&tile))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        let edge = vec![true, false, false];
+
+        assert_eq!(signature(&edge), vec![false, false, true]);
+        assert_eq!(signature(&[false, false, true]), vec![false, false, true]);
+        assert_eq!(signature(&[true, false, true]), vec![true, false, true]);
+    }
+}
@@ -1,57 +1,41 @@
-use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use helpers::{Grid, SectionError, Sections};
+
+use edges::{Edge, EdgeIndex};
+use pattern::Pattern;
+
+pub mod edges;
+pub mod pattern;
 
 type Matrix = Vec<Vec<bool>>;
 
 #[derive(Clone)]
-struct Tile {
+pub struct Tile {
     id: usize,
     matrix: Matrix,
     rotation: usize,
     flip_x: bool,
     flip_y: bool,
-    is_inner: bool,
-    is_side: bool,
-    is_corner: bool,
-    matches: Vec<usize>,
 }
 
 impl Tile {
-    fn from_chunk(chunk: &[&str]) -> Self {
-        let id: usize = (&chunk[0][5..=8]).parse().unwrap();
-        let mut matrix = vec![vec![false; 10]; 10];
-
-        for (y, line) in chunk[1..].iter().enumerate() {
-            for (x, char) in line.chars().enumerate() {
-                if char == '#' {
-                    matrix[y][x] = true;
-                }
-            }
-        }
-
-        Self {
-            id,
-            matrix,
-            rotation: 0,
-            flip_x: false,
-            flip_y: false,
-            is_inner: false,
-            is_side: false,
-            is_corner: false,
-            matches: vec![],
-        }
-    }
-
     fn orientations(&self) -> TileOrientations {
         TileOrientations::new((*self).clone())
     }
 
+    fn size(&self) -> usize {
+        self.matrix.len()
+    }
+
     fn rotate(&mut self) {
-        let mut matrix = vec![vec![false; 10]; 10];
+        let size = self.size();
+        let mut matrix = vec![vec![false; size]; size];
 
         for (y, row) in self.matrix.iter().enumerate() {
             for (x, cell) in row.iter().enumerate() {
-                matrix[x][9 - y] = *cell;
+                matrix[x][size - 1 - y] = *cell;
             }
         }
 
@@ -60,11 +44,12 @@ impl Tile {
     }
 
     fn flip_x(&mut self) {
-        let mut matrix = vec![vec![false; 10]; 10];
+        let size = self.size();
+        let mut matrix = vec![vec![false; size]; size];
 
         for (y, row) in self.matrix.iter().enumerate() {
             for (x, cell) in row.iter().enumerate() {
-                matrix[y][9 - x] = *cell;
+                matrix[y][size - 1 - x] = *cell;
             }
         }
 
@@ -72,30 +57,33 @@ impl Tile {
         self.matrix = matrix;
     }
 
+    fn top(&self) -> Edge {
+        self.matrix[0].clone()
+    }
+
+    fn bottom(&self) -> Edge {
+        self.matrix[self.matrix.len() - 1].clone()
+    }
+
+    fn left(&self) -> Edge {
+        self.matrix.iter().map(|row| row[0]).collect()
+    }
+
+    fn right(&self) -> Edge {
+        self.matrix.iter().map(|row| row[row.len() - 1]).collect()
+    }
+
+    // Top, right, bottom and left
+    fn edges(&self) -> [Edge; 4] {
+        [self.top(), self.right(), self.bottom(), self.left()]
+    }
+
     fn match_horizontal(left: &Tile, right: &Tile) -> bool {
-        left.matrix[0][9] == right.matrix[0][0]
-            && left.matrix[1][9] == right.matrix[1][0]
-            && left.matrix[2][9] == right.matrix[2][0]
-            && left.matrix[3][9] == right.matrix[3][0]
-            && left.matrix[4][9] == right.matrix[4][0]
-            && left.matrix[5][9] == right.matrix[5][0]
-            && left.matrix[6][9] == right.matrix[6][0]
-            && left.matrix[7][9] == right.matrix[7][0]
-            && left.matrix[8][9] == right.matrix[8][0]
-            && left.matrix[9][9] == right.matrix[9][0]
+        left.right() == right.left()
     }
 
     fn match_vertical(top: &Tile, bottom: &Tile) -> bool {
-        top.matrix[9][0] == bottom.matrix[0][0]
-            && top.matrix[9][1] == bottom.matrix[0][1]
-            && top.matrix[9][2] == bottom.matrix[0][2]
-            && top.matrix[9][3] == bottom.matrix[0][3]
-            && top.matrix[9][4] == bottom.matrix[0][4]
-            && top.matrix[9][5] == bottom.matrix[0][5]
-            && top.matrix[9][6] == bottom.matrix[0][6]
-            && top.matrix[9][7] == bottom.matrix[0][7]
-            && top.matrix[9][8] == bottom.matrix[0][8]
-            && top.matrix[9][9] == bottom.matrix[0][9]
+        top.bottom() == bottom.top()
     }
 }
 
@@ -103,10 +91,9 @@ impl Display for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(
             f,
-            "Tile ({} x: {} y: {} inner {}) {}:",
-            self.rotation, self.flip_x, self.flip_y, self.is_inner, self.id
+            "Tile ({} x: {} y: {}) {}:",
+            self.rotation, self.flip_x, self.flip_y, self.id
         )?;
-        writeln!(f, "Matches: {:?}", self.matches)?;
         for row in self.matrix.iter() {
             for cell in row {
                 write!(f, "{}", if true == *cell { "#" } else { "." })?;
@@ -123,10 +110,9 @@ impl Debug for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(
             f,
-            "Tile ({} x: {} y: {} inner {}) {}:",
-            self.rotation, self.flip_x, self.flip_y, self.is_inner, self.id
+            "Tile ({} x: {} y: {}) {}:",
+            self.rotation, self.flip_x, self.flip_y, self.id
         )?;
-        writeln!(f, "Matches: {:?}", self.matches)?;
         for row in self.matrix.iter() {
             for cell in row {
                 write!(f, "{}", if true == *cell { "#" } else { "." })?;
@@ -139,6 +125,137 @@ impl Debug for Tile {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum TileError {
+    Header(String),
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    // Tiles are squares with at least one cell inside the border
+    Size {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl Display for TileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TileError::Header(header) => {
+                write!(f, "expected `Tile <id>:`, found \"{}\"", header)
+            }
+            TileError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {}: expected {} cells, found {}",
+                row, expected, found
+            ),
+            TileError::Size { width, height } => write!(
+                f,
+                "expected a square tile of at least 3x3 cells, found {}x{}",
+                width, height
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TileError {}
+
+#[derive(Debug, PartialEq)]
+pub enum AssemblyError {
+    Empty,
+    // A tile with another size than the first one
+    Size {
+        id: usize,
+        expected: usize,
+        found: usize,
+    },
+    NoCorner,
+    // Row and column counted from 1
+    Missing {
+        row: usize,
+        column: usize,
+    },
+    // The first row is `width` tiles long but the rest do not fill whole rows
+    Shape {
+        tiles: usize,
+        width: usize,
+    },
+}
+
+impl Display for AssemblyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssemblyError::Empty => write!(f, "there are no tiles"),
+            AssemblyError::Size {
+                id,
+                expected,
+                found,
+            } => write!(
+                f,
+                "tile {}: expected {}x{} cells, found {}x{}",
+                id, expected, expected, found, found
+            ),
+            AssemblyError::NoCorner => write!(f, "no tile fits in a corner"),
+            AssemblyError::Missing { row, column } => {
+                write!(f, "no tile fits at row {}, column {}", row, column)
+            }
+            AssemblyError::Shape { tiles, width } => {
+                write!(f, "{} tiles do not fill rows of {} tiles", tiles, width)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssemblyError {}
+
+impl FromStr for Tile {
+    type Err = TileError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lines = input.lines();
+        let header = lines.next().unwrap_or_default();
+        let id = header
+            .strip_prefix("Tile ")
+            .and_then(|header| header.strip_suffix(':'))
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| TileError::Header(header.to_string()))?;
+        let matrix: Matrix = lines
+            .map(|line| line.chars().map(|char| char == '#').collect())
+            .collect();
+
+        let width = matrix.first().map_or(0, Vec::len);
+
+        if let Some(row) = matrix.iter().position(|row| row.len() != width) {
+            return Err(TileError::Ragged {
+                row: row + 1,
+                expected: width,
+                found: matrix[row].len(),
+            });
+        }
+
+        if width != matrix.len() || width < 3 {
+            return Err(TileError::Size {
+                width,
+                height: matrix.len(),
+            });
+        }
+
+        Ok(Self {
+            id,
+            matrix,
+            rotation: 0,
+            flip_x: false,
+            flip_y: false,
+        })
+    }
+}
+
 struct TileOrientations {
     index: usize,
     tile: Tile,
@@ -179,306 +296,167 @@ impl Iterator for TileOrientations {
     }
 }
 
-fn parse_input(input: &Vec<&str>) -> Vec<Tile> {
-    let mut tiles = Vec::new();
-
-    for chunk in input.chunks(11) {
-        tiles.push(Tile::from_chunk(chunk));
-    }
-
-    tiles
+pub fn parse_tiles(input: &str) -> Result<Vec<Tile>, SectionError<TileError>> {
+    Sections::new(input).parse_all()
 }
 
-fn find_corners(tiles: &Vec<Tile>) -> Vec<&Tile> {
-    let mut corner_tiles = Vec::new();
-
-    for tile in tiles.iter() {
-        let mut is_corner = true;
-
-        for tile_variation in tile.orientations() {
-            let mut left_count = 0;
-            let mut right_count = 0;
-            let mut top_count = 0;
-            let mut bottom_count = 0;
-
-            for other in tiles.iter() {
-                if tile_variation.id == other.id {
-                    continue;
-                }
-
-                for other_variation in other.orientations() {
-                    // match left
-                    if Tile::match_horizontal(&other_variation, &tile_variation) {
-                        left_count += 1;
-                    }
-
-                    // match right
-                    if Tile::match_horizontal(&tile_variation, &other_variation) {
-                        right_count += 1;
-                    }
-
-                    // match top
-                    if Tile::match_horizontal(&tile_variation, &other_variation) {
-                        top_count += 1;
-                    }
-
-                    // match bottom
-                    if Tile::match_horizontal(&other_variation, &tile_variation) {
-                        bottom_count += 1;
-                    }
-                }
-            }
-
-            is_corner = is_corner && 2 == top_count + left_count + right_count + bottom_count;
-        }
-
-        if is_corner {
-            corner_tiles.push(tile);
-        }
-    }
+type Placed = (usize, Tile);
+
+// An unused tile turned to fit right of `left` and below `above`
+fn place(
+    tiles: &[Tile],
+    index: &EdgeIndex,
+    used: &mut [bool],
+    left: Option<&Placed>,
+    above: Option<&Placed>,
+) -> Option<Placed> {
+    let (previous, edge) = match (left, above) {
+        (Some((previous, tile)), _) => (*previous, tile.right()),
+        (None, Some((previous, tile))) => (*previous, tile.bottom()),
+        (None, None) => return None,
+    };
 
-    corner_tiles
+    let placed = index
+        .matching(&edge, previous)
+        .into_iter()
+        .filter(|&candidate| !used[candidate])
+        .find_map(|candidate| {
+            tiles[candidate]
+                .orientations()
+                .find(|tile| {
+                    left.is_none_or(|(_, left)| Tile::match_horizontal(left, tile))
+                        && above.is_none_or(|(_, above)| Tile::match_vertical(above, tile))
+                })
+                .map(|tile| (candidate, tile))
+        })?;
+
+    used[placed.0] = true;
+    Some(placed)
 }
 
-fn find_sides(tiles: &Vec<Tile>) -> Vec<Tile> {
-    let mut new_tiles = Vec::new();
-
-    for tile in tiles.iter() {
-        let mut matches = HashSet::new();
-        let mut left_count = 0;
-        let mut right_count = 0;
-        let mut top_count = 0;
-        let mut bottom_count = 0;
-
-        for tile_variation in tile.orientations() {
-            for (other_index, other) in tiles.iter().enumerate() {
-                if tile.id == other.id {
-                    continue;
-                }
-
-                let other_variation = other;
-
-                // match left
-                if Tile::match_horizontal(&other_variation, &tile_variation) {
-                    matches.insert(other_index);
-                    left_count += 1;
-                }
-
-                // match right
-                if Tile::match_horizontal(&tile_variation, &other_variation) {
-                    matches.insert(other_index);
-                    right_count += 1;
-                }
-
-                // match top
-                if Tile::match_vertical(&other_variation, &tile_variation) {
-                    matches.insert(other_index);
-                    top_count += 1;
-                }
-
-                // match bottom
-                if Tile::match_vertical(&tile_variation, &other_variation) {
-                    matches.insert(other_index);
-                    bottom_count += 1;
-                }
-            }
+// Places every tile row by row, starting from a corner turned so that its
+// unmatched edges face up and left. The first row ends at the first tile with
+// nothing on its right, the other rows have the same length. Each next tile
+// is found through the edge it has to fit, so there is nothing to backtrack.
+fn assemble(tiles: &[Tile], index: &EdgeIndex) -> Result<Vec<Vec<Tile>>, AssemblyError> {
+    let corner = *index
+        .corners(tiles)
+        .first()
+        .ok_or(AssemblyError::NoCorner)?;
+    let first = tiles[corner]
+        .orientations()
+        .find(|tile| index.is_border(&tile.top(), corner) && index.is_border(&tile.left(), corner))
+        .ok_or(AssemblyError::NoCorner)?;
+
+    let mut used = vec![false; tiles.len()];
+    used[corner] = true;
+    let mut rows = vec![vec![(corner, first)]];
+
+    loop {
+        let (previous, tile) = rows[0].last().unwrap();
+
+        if index.is_border(&tile.right(), *previous) {
+            break;
         }
 
-        let count = left_count + right_count + top_count + bottom_count;
-        let mut tile = tile.clone();
-        tile.is_inner = count == 4;
-        tile.is_side = count == 3;
-        tile.is_corner = count == 2;
-        tile.matches = matches.into_iter().collect();
-
-        new_tiles.push(tile);
+        let placed =
+            place(tiles, index, &mut used, rows[0].last(), None).ok_or(AssemblyError::Missing {
+                row: 1,
+                column: rows[0].len() + 1,
+            })?;
+        rows[0].push(placed);
     }
 
-    new_tiles
-}
+    let width = rows[0].len();
 
-fn find_valid(
-    tiles: &Vec<Tile>,
-    size: usize,
-    solution: Vec<Tile>,
-    deep: usize,
-) -> Option<Vec<Tile>> {
-    if deep + 1 >= tiles.len() {
-        return Some(solution);
+    if !tiles.len().is_multiple_of(width) {
+        return Err(AssemblyError::Shape {
+            tiles: tiles.len(),
+            width,
+        });
     }
 
-    let is_new_line = (deep + 1) % size == 0;
-
-    let tile = if is_new_line {
-        &solution[deep + 1 - size]
-    } else {
-        solution.last().unwrap()
-    };
-
-    for mtch in tile.matches.iter() {
-        let match_tile = &tiles.get(*mtch).unwrap();
-
-        for match_tile_variant in match_tile.orientations() {
-            if (is_new_line && Tile::match_vertical(tile, &match_tile_variant))
-                || (false == is_new_line && Tile::match_horizontal(tile, &match_tile_variant))
-            {
-                let mut solution = solution.clone();
-                solution.push(match_tile_variant.clone());
-                match find_valid(tiles, size, solution, deep + 1) {
-                    None => {}
-                    Some(solution) => return Some(solution),
-                }
-            }
+    for row in 1..tiles.len() / width {
+        let mut current: Vec<Placed> = Vec::with_capacity(width);
+
+        for (column, above) in rows[row - 1].iter().enumerate() {
+            let placed = place(tiles, index, &mut used, current.last(), Some(above)).ok_or(
+                AssemblyError::Missing {
+                    row: row + 1,
+                    column: column + 1,
+                },
+            )?;
+            current.push(placed);
         }
-    }
-
-    None
-}
 
-fn count_monsters(image: &Vec<Vec<bool>>, monster_coords: &Vec<(usize, usize)>) -> usize {
-    let monster_width = 20;
-    let monster_height = 3;
-
-    let mut monsters = 0;
-    let size = image.len();
-
-    for (y, row) in image.iter().enumerate() {
-        'x: for (x, _) in row.iter().enumerate() {
-            if x < size - monster_width && y < size - monster_height {
-                for coords in monster_coords.iter() {
-                    if image[y + coords.0][x + coords.1] == false {
-                        continue 'x;
-                    }
-                }
-
-                monsters += 1;
-            }
-        }
+        rows.push(current);
     }
 
-    monsters
+    Ok(rows
+        .into_iter()
+        .map(|row| row.into_iter().map(|(_, tile)| tile).collect())
+        .collect())
 }
 
-fn count_cells(image: &Vec<Vec<bool>>) -> usize {
-    let mut count = 0;
+// The tiles put together without their borders
+pub fn assemble_image(tiles: &[Tile]) -> Result<Grid<bool>, AssemblyError> {
+    let size = tiles.first().ok_or(AssemblyError::Empty)?.size();
 
-    for row in image.iter() {
-        for cell in row.iter() {
-            if *cell == true {
-                count += 1;
-            }
-        }
+    if let Some(tile) = tiles.iter().find(|tile| tile.size() != size) {
+        return Err(AssemblyError::Size {
+            id: tile.id,
+            expected: size,
+            found: tile.size(),
+        });
     }
 
-    count
-}
-
-fn rotate(matrix: &Matrix, size: usize) -> Matrix {
-    let mut new_matrix = vec![vec![false; size]; size];
+    let rows = assemble(tiles, &EdgeIndex::new(tiles))?;
+    let inner = size - 2;
+    let mut image = Grid::filled(inner * rows[0].len(), inner * rows.len(), false)
+        .expect("Something went wrong sizing the image!");
 
-    for (y, row) in matrix.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            new_matrix[x][(size - 1) - y] = *cell;
+    for (row, row_tiles) in rows.iter().enumerate() {
+        for (column, tile) in row_tiles.iter().enumerate() {
+            for (y, cells) in tile.matrix[1..=inner].iter().enumerate() {
+                for (x, cell) in cells[1..=inner].iter().enumerate() {
+                    image[(column * inner + x, row * inner + y)] = *cell;
+                }
+            }
         }
     }
 
-    new_matrix
+    Ok(image)
 }
 
-fn flip_x(matrix: &Matrix, size: usize) -> Matrix {
-    let mut new_matrix = vec![vec![false; size]; size];
-
-    for (y, row) in matrix.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            new_matrix[y][(size - 1) - x] = *cell;
-        }
-    }
-
-    new_matrix
+pub fn part1(input: &str) -> usize {
+    part1_parsed(&parse_tiles(input).unwrap_or_else(|error| panic!("{}", error)))
 }
 
-pub fn part1(input: &Vec<&str>) -> usize {
-    let tiles = parse_input(input);
-
-    find_corners(&tiles)
+pub fn part1_parsed(tiles: &[Tile]) -> usize {
+    EdgeIndex::new(tiles)
+        .corners(tiles)
         .iter()
-        .fold(1, |num, tile| num * tile.id)
+        .fold(1, |num, &tile| num * tiles[tile].id)
 }
 
-pub fn part2(input: &Vec<&str>) -> usize {
-    let tiles = parse_input(input);
-    let size = (tiles.len() as f64).sqrt() as usize;
-    let tiles = find_sides(&tiles);
-
-    let corners = tiles
-        .iter()
-        .filter(|tile| tile.is_corner)
-        .collect::<Vec<&Tile>>();
-
-    let mut ordered_tiles = Vec::new();
-
-    'outer: for corner in corners {
-        for corner_version in corner.orientations() {
-            match find_valid(&tiles, size, vec![corner_version.clone()], 0) {
-                None => {}
-                Some(solution) => {
-                    ordered_tiles = solution;
-                    break 'outer;
-                }
-            }
-        }
-    }
-
-    let mut image = vec![vec![false; 8 * size]; 8 * size];
-
-    for (i_tile, tile) in ordered_tiles.iter().enumerate() {
-        for (y, row) in tile.matrix.iter().enumerate() {
-            for (x, cell) in row.iter().enumerate() {
-                if x > 0 && x < 9 && y > 0 && y < 9 {
-                    image[y - 1 + (i_tile / size) * 8][x - 1 + (i_tile % size) * 8] = *cell;
-                }
-            }
-        }
-    }
-
-    let monster_coords = "                  # #    ##    ##    ### #  #  #  #  #  #   "
-        .chars()
-        .enumerate()
-        .fold(vec![], |mut coords, (index, cell)| {
-            if cell == '#' {
-                coords.push((index / 20, index % 20));
-            }
-
-            coords
-        });
-
-    for i in 0..8 {
-        if i > 0 {
-            image = rotate(&image, 8 * size);
-        }
-
-        if i == 4 {
-            image = flip_x(&image, 8 * size);
-        }
-
-        let monsters = count_monsters(&image, &monster_coords);
+pub fn part2(input: &str) -> usize {
+    part2_parsed(&parse_tiles(input).unwrap_or_else(|error| panic!("{}", error)))
+}
 
-        if monsters > 0 {
-            return count_cells(&image) - monsters * monster_coords.len();
-        }
-    }
+pub fn part2_parsed(tiles: &[Tile]) -> usize {
+    let image = assemble_image(tiles).unwrap_or_else(|error| panic!("{}", error));
 
-    0
+    Pattern::sea_monster().roughness(&image)
 }
 
 #[cfg(test)]
 mod tests {
-    use helpers::input_lines;
+    use helpers::Random;
 
     use super::*;
 
-    fn input<'a>() -> Vec<&'a str> {
-        let input = "
+    fn input<'a>() -> &'a str {
+        "
 Tile 2311:
 ..##.#..#.
 ##..#.....
@@ -586,18 +564,186 @@ Tile 3079:
 ..#.###...
 ..#.......
 ..#.###...
-";
-        input_lines(input)
+"
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&input()), 20899048083289)
+        assert_eq!(part1(input()), 20899048083289)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&input()), 273)
+        assert_eq!(part2(input()), 273)
+    }
+
+    #[test]
+    fn test_edge_index() {
+        let tiles = parse_tiles(input()).unwrap();
+        let index = EdgeIndex::new(&tiles);
+        let ids = |positions: Vec<usize>| {
+            let mut ids: Vec<usize> = positions.iter().map(|&tile| tiles[tile].id).collect();
+            ids.sort_unstable();
+            ids
+        };
+
+        assert_eq!(ids(index.corners(&tiles)), vec![1171, 1951, 2971, 3079]);
+        assert_eq!(ids(index.sides(&tiles)), vec![1489, 2311, 2473, 2729]);
+
+        let center = tiles.iter().position(|tile| tile.id == 1427).unwrap();
+        assert_eq!(
+            ids(index.neighbours(&tiles, center)),
+            vec![1489, 2311, 2473, 2729]
+        );
+    }
+
+    #[test]
+    fn test_assemble() {
+        let tiles = parse_tiles(input()).unwrap();
+        let rows = assemble(&tiles, &EdgeIndex::new(&tiles)).unwrap();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1][1].id, 1427);
+        for (y, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), 3);
+
+            for (x, tile) in row.iter().enumerate() {
+                if x > 0 {
+                    assert!(Tile::match_horizontal(&row[x - 1], tile));
+                }
+                if y > 0 {
+                    assert!(Tile::match_vertical(&rows[y - 1][x], tile));
+                }
+            }
+        }
+    }
+
+    // Cuts a random picture into `width` by `height` tiles of `size` cells,
+    // neighbours share their border. Returns the tiles, randomly turned, and
+    // the picture without the borders.
+    fn cut(width: usize, height: usize, size: usize) -> (Vec<Tile>, Grid<bool>) {
+        let mut random = Random::new(20);
+
+        let step = size - 1;
+        let mut picture = Grid::filled(width * step + 1, height * step + 1, false).unwrap();
+        for point in picture.points().collect::<Vec<_>>() {
+            picture[point] = random.below(2) == 0;
+        }
+
+        let mut tiles = Vec::new();
+        for row in 0..height {
+            for column in 0..width {
+                let matrix = (0..size)
+                    .map(|y| {
+                        (0..size)
+                            .map(|x| picture[(column * step + x, row * step + y)])
+                            .collect()
+                    })
+                    .collect();
+                let tile = Tile {
+                    id: 100_000 + tiles.len(),
+                    matrix,
+                    rotation: 0,
+                    flip_x: false,
+                    flip_y: false,
+                };
+
+                tiles.push(tile.orientations().nth(random.below(8) as usize).unwrap());
+            }
+        }
+
+        let inner: Vec<Vec<bool>> = (0..picture.height())
+            .filter(|y| y % step != 0)
+            .map(|y| {
+                (0..picture.width())
+                    .filter(|x| x % step != 0)
+                    .map(|x| picture[(x, y)])
+                    .collect()
+            })
+            .collect();
+
+        (tiles, Grid::from_rows(inner).unwrap())
+    }
+
+    #[test]
+    fn test_rectangular_image() {
+        for &(width, height) in [(4, 3), (5, 1), (1, 1)].iter() {
+            let (tiles, expected) = cut(width, height, 12);
+            let image = assemble_image(&tiles).unwrap();
+
+            assert!(
+                expected.orientations().contains(&image),
+                "{}x{}",
+                width,
+                height
+            );
+        }
+    }
+
+    #[test]
+    fn test_assembly_errors() {
+        let mut tiles = parse_tiles(input()).unwrap();
+        let (mut other, _) = cut(1, 1, 10);
+
+        assert_eq!(assemble_image(&[]), Err(AssemblyError::Empty));
+
+        let center = tiles.iter().position(|tile| tile.id == 1427).unwrap();
+        std::mem::swap(&mut tiles[center], &mut other[0]);
+        assert_eq!(
+            assemble_image(&tiles).unwrap_err().to_string(),
+            "no tile fits at row 2, column 2"
+        );
+
+        tiles.remove(center);
+        assert_eq!(
+            assemble_image(&tiles),
+            Err(AssemblyError::Shape { tiles: 8, width: 3 })
+        );
+
+        tiles.push(cut(1, 1, 4).0.remove(0));
+        assert_eq!(
+            assemble_image(&tiles).unwrap_err().to_string(),
+            "tile 100000: expected 10x10 cells, found 4x4"
+        );
+    }
+
+    #[test]
+    fn test_tile_sizes() {
+        let tiles = parse_tiles("Tile 123456:\n#..#\n....\n.##.\n#...").unwrap();
+        assert_eq!(tiles[0].id, 123456);
+        assert_eq!(tiles[0].size(), 4);
+
+        assert_eq!(
+            "Tile 1:\n#..\n..\n...".parse::<Tile>().err(),
+            Some(TileError::Ragged {
+                row: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "Tile 1:\n#.\n..".parse::<Tile>().err(),
+            Some(TileError::Size {
+                width: 2,
+                height: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_tiles("Tile 2311:\n..##.#..#.\n\nTile x:\n")
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "section 1, line 1: cannot parse \"Tile 2311:\n..##.#..#.\": expected a square tile of at least 3x3 cells, found 10x1"
+        );
+        assert_eq!(
+            parse_tiles("Tile x:").err().unwrap().to_string(),
+            "section 1, line 1: cannot parse \"Tile x:\": expected `Tile <id>:`, found \"Tile x:\""
+        );
     }
 
     //     #[test]
@@ -1,8 +1,12 @@
-use helpers::{input_lines, run};
+use helpers::{load_input, run_parsed, run_with_parse};
 
 fn main() {
-    let input: Vec<&str> = input_lines(include_str!("../input.txt"));
+    let raw_input = load_input(include_str!("../input.txt"));
 
-    run("part1", || day20::part1(&input));
-    run("part2", || day20::part2(&input));
+    let tiles = run_with_parse(
+        "part1",
+        || day20::parse_tiles(&raw_input).unwrap_or_else(|error| panic!("{}", error)),
+        |tiles: &Vec<_>| day20::part1_parsed(tiles),
+    );
+    run_parsed("part2", &tiles, |tiles| day20::part2_parsed(tiles));
 }
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use helpers::{Grid, Point};
+
+pub const SEA_MONSTER: &str = concat!(
+    "                  # \n",
+    "#    ##    ##    ###\n",
+    " #  #  #  #  #  #   ",
+);
+
+// The `#` cells of an ASCII drawing, anything else matches any cell. Offsets
+// are from the top left corner of the box around the `#` cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<Point>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PatternError {
+    Empty,
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::Empty => write!(f, "the pattern has no `#` cells"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let cells: Vec<Point> = input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, char)| char == '#')
+                    .map(move |(x, _)| (x, y))
+            })
+            .collect();
+
+        let left = cells
+            .iter()
+            .map(|&(x, _)| x)
+            .min()
+            .ok_or(PatternError::Empty)?;
+        let top = cells.iter().map(|&(_, y)| y).min().unwrap();
+        let cells: Vec<Point> = cells.iter().map(|&(x, y)| (x - left, y - top)).collect();
+
+        Ok(Pattern {
+            width: cells.iter().map(|&(x, _)| x + 1).max().unwrap(),
+            height: cells.iter().map(|&(_, y)| y + 1).max().unwrap(),
+            cells,
+        })
+    }
+}
+
+// The image turned the way the pattern was found, and where
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matches {
+    pub image: Grid<bool>,
+    pub positions: Vec<Point>,
+}
+
+impl Pattern {
+    pub fn sea_monster() -> Self {
+        SEA_MONSTER.parse().unwrap()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // The top left corner of every match, matches may overlap
+    pub fn find(&self, image: &Grid<bool>) -> Vec<Point> {
+        if image.width() < self.width || image.height() < self.height {
+            return Vec::new();
+        }
+
+        let mut positions = Vec::new();
+
+        for y in 0..=image.height() - self.height {
+            for x in 0..=image.width() - self.width {
+                if self.cells.iter().all(|&(dx, dy)| image[(x + dx, y + dy)]) {
+                    positions.push((x, y));
+                }
+            }
+        }
+
+        positions
+    }
+
+    // The first orientation of the image where the pattern shows up
+    pub fn search(&self, image: &Grid<bool>) -> Option<Matches> {
+        image.orientations().into_iter().find_map(|image| {
+            let positions = self.find(&image);
+
+            match positions.len() {
+                0 => None,
+                _ => Some(Matches { image, positions }),
+            }
+        })
+    }
+
+    // Cells covered by the matches at `positions`, counted once when they overlap
+    pub fn covered(&self, positions: &[Point]) -> HashSet<Point> {
+        positions
+            .iter()
+            .flat_map(|&(x, y)| self.cells.iter().map(move |&(dx, dy)| (x + dx, y + dy)))
+            .collect()
+    }
+
+    // Set cells that are not part of any match
+    pub fn roughness(&self, image: &Grid<bool>) -> usize {
+        let set = image.count(|&cell| cell);
+
+        match self.search(image) {
+            Some(matches) => set - self.covered(&matches.positions).len(),
+            None => set,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(input: &str) -> Grid<bool> {
+        Grid::parse(input, |char| Some(char == '#')).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let monster = Pattern::sea_monster();
+
+        assert_eq!((monster.width(), monster.height()), (20, 3));
+        assert_eq!(monster.cells.len(), 15);
+
+        let pattern: Pattern = "\n   \n  .#\n  ##\n".parse().unwrap();
+        assert_eq!((pattern.width(), pattern.height()), (2, 2));
+        assert_eq!(pattern.cells, vec![(1, 0), (0, 1), (1, 1)]);
+
+        assert_eq!(" .. \n".parse::<Pattern>(), Err(PatternError::Empty));
+    }
+
+    #[test]
+    fn test_find_at_the_edges() {
+        let pattern: Pattern = "##\n##".parse().unwrap();
+        let image = image(
+            "
+            ....
+            ..##
+            ..##
+            ",
+        );
+
+        assert_eq!(pattern.find(&image), vec![(2, 1)]);
+        assert_eq!(pattern.find(&Grid::filled(1, 1, true).unwrap()), vec![]);
+    }
+
+    #[test]
+    fn test_overlapping_matches() {
+        let pattern: Pattern = "##".parse().unwrap();
+        let image = image("###.#");
+
+        assert_eq!(pattern.find(&image), vec![(0, 0), (1, 0)]);
+        assert_eq!(pattern.roughness(&image), 1);
+    }
+
+    #[test]
+    fn test_search_orientations() {
+        let pattern: Pattern = "#..\n###".parse().unwrap();
+        let image = image(
+            "
+            .##.
+            .#..
+            .#..
+            ",
+        );
+
+        let matches = pattern.search(&image).unwrap();
+        assert_eq!(matches.positions.len(), 1);
+        assert_eq!(pattern.find(&matches.image), matches.positions);
+        assert_eq!(pattern.roughness(&image), 0);
+
+        let pattern: Pattern = "###\n###".parse().unwrap();
+        assert_eq!(pattern.search(&image), None);
+        assert_eq!(pattern.roughness(&image), 4);
+    }
+}
@@ -1,7 +1,9 @@
 use std::collections::VecDeque;
 use std::hash::{Hash, Hasher};
+use std::num::ParseIntError;
 
 use fxhash::{FxHashSet, FxHasher64};
+use helpers::{SectionError, Sections};
 
 #[derive(PartialEq)]
 enum Player {
@@ -9,23 +11,13 @@ enum Player {
     Crab,
 }
 
-fn parse(input: &Vec<&str>) -> (VecDeque<u8>, VecDeque<u8>) {
-    let mut player_1_cards = VecDeque::new();
-    let mut player_2_cards = VecDeque::new();
+fn parse(input: &str) -> Result<(VecDeque<u8>, VecDeque<u8>), SectionError<ParseIntError>> {
+    let sections = Sections::new(input);
 
-    for (i, line) in input.iter().enumerate() {
-        if i == 0 || i == input.len() / 2 {
-            continue;
-        }
-
-        if i < input.len() / 2 {
-            player_1_cards.push_back(line.parse::<u8>().unwrap());
-        } else {
-            player_2_cards.push_back(line.parse::<u8>().unwrap());
-        }
-    }
-
-    (player_1_cards, player_2_cards)
+    Ok((
+        sections.parse_lines("Player 1")?,
+        sections.parse_lines("Player 2")?,
+    ))
 }
 
 fn winner_cards<'a>(
@@ -116,15 +108,17 @@ fn play_v2(player_1_cards: &mut VecDeque<u8>, player_2_cards: &mut VecDeque<u8>)
     }
 }
 
-pub fn part1(input: &Vec<&str>) -> usize {
-    let (mut player_1_cards, mut player_2_cards) = parse(input);
+pub fn part1(input: &str) -> usize {
+    let (mut player_1_cards, mut player_2_cards) =
+        parse(input).unwrap_or_else(|error| panic!("{}", error));
 
     play_v1(&mut player_1_cards, &mut player_2_cards);
     calc_points(winner_cards(&player_1_cards, &player_2_cards))
 }
 
-pub fn part2(input: &Vec<&str>) -> usize {
-    let (mut player_1_cards, mut player_2_cards) = parse(input);
+pub fn part2(input: &str) -> usize {
+    let (mut player_1_cards, mut player_2_cards) =
+        parse(input).unwrap_or_else(|error| panic!("{}", error));
 
     play_v2(&mut player_1_cards, &mut player_2_cards);
     calc_points(winner_cards(&player_1_cards, &player_2_cards))
@@ -132,12 +126,10 @@ pub fn part2(input: &Vec<&str>) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use helpers::input_lines;
-
     use super::*;
 
-    fn input<'a>() -> Vec<&'a str> {
-        let input = "
+    fn input<'a>() -> &'a str {
+        "
 Player 1:
 9
 2
@@ -150,17 +142,16 @@ Player 2:
 8
 4
 7
-10";
-        input_lines(input)
+10"
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&input()), 306)
+        assert_eq!(part1(input()), 306)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&input()), 291)
+        assert_eq!(part2(input()), 291)
     }
 }
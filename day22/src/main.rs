@@ -1,8 +1,8 @@
-use helpers::{input_lines, run};
+use helpers::{load_input, run};
 
 fn main() {
-    let input: Vec<&str> = input_lines(include_str!("../input.txt"));
+    let raw_input = load_input(include_str!("../input.txt"));
 
-    run("part1", || day22::part1(&input));
-    run("part2", || day22::part2(&input));
+    run("part1", || day22::part1(&raw_input));
+    run("part2", || day22::part2(&raw_input));
 }
@@ -1,7 +1,8 @@
-use helpers::{parse_split_input, run};
+use helpers::{load_input, parse_split_input, run};
 
 fn main() {
-    let input: Vec<usize> = parse_split_input(include_str!("../input.txt"), "");
+    let raw_input = load_input(include_str!("../input.txt"));
+    let input: Vec<usize> = parse_split_input(&raw_input, "");
 
     run("part1", || day23::part1(&input));
     run("part2", || day23::part2(&input));
@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::str::FromStr;
 
+use helpers::{Automaton, HexGrid, Rule};
+
 #[derive(Debug)]
 enum Direction {
     East,
@@ -27,6 +29,20 @@ impl FromStr for Direction {
     }
 }
 
+impl Direction {
+    // Axial coordinates, as used by `HexGrid`
+    fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::East => (1, 0),
+            Direction::Southeast => (0, 1),
+            Direction::Southwest => (-1, 1),
+            Direction::West => (-1, 0),
+            Direction::Northwest => (0, -1),
+            Direction::Northeast => (1, -1),
+        }
+    }
+}
+
 fn parse(input: &Vec<&str>) -> Vec<Vec<Direction>> {
     let mut steps_list = Vec::new();
 
@@ -50,183 +66,33 @@ fn parse(input: &Vec<&str>) -> Vec<Vec<Direction>> {
     steps_list
 }
 
-fn calc_grid(input: &Vec<&str>) -> HashMap<(i32, i32), bool> {
-    let steps_list = parse(input);
-    let mut grid: HashMap<(i32, i32), bool> = HashMap::new();
-
-    for steps in steps_list {
-        let mut x: f64 = 0.0;
-        let mut y = 0;
-
-        for step in steps.iter() {
-            match step {
-                Direction::East => {
-                    x += 1.0;
-                }
-                Direction::Southeast => {
-                    x += 0.5;
-                    y -= 1;
-                }
-                Direction::Southwest => {
-                    x -= 0.5;
-                    y -= 1;
-                }
-                Direction::West => {
-                    x -= 1.0;
-                }
-                Direction::Northwest => {
-                    x -= 0.5;
-                    y += 1;
-                }
-                Direction::Northeast => {
-                    x += 0.5;
-                    y += 1;
-                }
-            }
-        }
-
-        let x_ = x.floor() as i32;
+fn black_tiles(input: &Vec<&str>) -> HashSet<(i32, i32)> {
+    let mut black_tiles = HashSet::new();
 
-        let tile = grid.get(&(x_, y));
+    for steps in parse(input) {
+        let tile = steps.iter().fold((0, 0), |(q, r), step| {
+            let (dq, dr) = step.offset();
+            (q + dq, r + dr)
+        });
 
-        if tile.is_some() {
-            let tile = tile.unwrap().to_owned();
-            grid.insert((x_, y), !tile);
-        } else {
-            grid.insert((x_, y), true);
+        if !black_tiles.remove(&tile) {
+            black_tiles.insert(tile);
         }
     }
 
-    grid
-}
-
-fn black_neighbors(grid: &HashMap<(i32, i32), bool>, x: i32, y: i32) -> usize {
-    let mut black_neighbors = 0;
-
-    if y % 2 == 0 {
-        if *grid.get(&(x + 1, y)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x, y + 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x - 1, y + 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x - 1, y)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x - 1, y - 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x, y - 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-    } else {
-        if *grid.get(&(x + 1, y)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x + 1, y + 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x, y + 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x - 1, y)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x, y - 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-
-        if *grid.get(&(x + 1, y - 1)).unwrap_or(&false) {
-            black_neighbors += 1;
-        }
-    }
-
-    black_neighbors
+    black_tiles
 }
 
 pub fn part1(input: &Vec<&str>) -> usize {
-    let grid = calc_grid(input);
-
-    let mut black_tiles_count = 0;
-    for (_, tile) in grid.iter() {
-        if *tile {
-            black_tiles_count += 1;
-        }
-    }
-
-    black_tiles_count
+    black_tiles(input).len()
 }
 
+// Black tiles stay black with 1 or 2 black neighbours, white tiles flip with
+// exactly 2.
 pub fn part2(input: &Vec<&str>) -> usize {
-    let mut grid = calc_grid(input);
-
-    let mut x_max = 0;
-    let mut x_min = 0;
-    let mut y_max = 0;
-    let mut y_min = 0;
-
-    for (&(x, y), _) in grid.iter() {
-        if x < x_min {
-            x_min = x;
-        }
-        if x > x_max {
-            x_max = x;
-        }
-        if y < y_min {
-            y_min = y;
-        }
-        if y > y_max {
-            y_max = y;
-        }
-    }
-
-    let mut x_range = x_min..=x_max;
-    let mut y_range = y_min..=y_max;
-
-    for _ in 0..100 {
-        let mut new_grid: HashMap<(i32, i32), bool> = HashMap::new();
-
-        x_range = x_range.start() - 2..=x_range.end() + 2;
-        y_range = y_range.start() - 2..=y_range.end() + 2;
-
-        for y in y_range.clone() {
-            for x in x_range.clone() {
-                let black_neighbors = black_neighbors(&grid, x, y);
-                let mut status = *grid.get(&(x, y)).unwrap_or(&false);
-
-                if status && (black_neighbors == 0 || black_neighbors > 2) {
-                    status = false;
-                } else if false == status && black_neighbors == 2 {
-                    status = true;
-                }
-
-                new_grid.insert((x, y), status);
-            }
-        }
-
-        grid = new_grid;
-    }
-
-    let mut black_tiles_count = 0;
-    for (_, &tile) in grid.iter() {
-        if tile {
-            black_tiles_count += 1;
-        }
-    }
+    let mut floor = Automaton::new(HexGrid, Rule::new(&[2], &[1, 2]), black_tiles(input)).unwrap();
 
-    black_tiles_count
+    floor.run(100).population()
 }
 
 #[cfg(test)]
@@ -1,7 +1,8 @@
-use helpers::{parse_input, run};
+use helpers::{load_input, parse_input, run};
 
 fn main() {
-    let input: Vec<usize> = parse_input(include_str!("../input.txt"));
+    let raw_input = load_input(include_str!("../input.txt"));
+    let input: Vec<usize> = parse_input(&raw_input);
 
     run("part1", || day25::part1(&input));
 }
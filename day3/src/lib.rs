@@ -1,38 +1,34 @@
-fn count_trees(input: &Vec<&str>, right: usize, bottom: usize) -> usize {
-    let mut position = (0usize, 0usize);
-    let mut trees = 0;
+use helpers::Grid;
 
-    let width = input.get(0).unwrap().len();
-    let height = input.len();
-
-    loop {
-        position = ((position.0 + right) % width, position.1 + bottom);
-
-        if position.1 >= height {
-            break;
-        }
-
-        let line = input.get(position.1).unwrap();
-        let square = line.chars().nth(position.0).unwrap();
-
-        if '#' == square {
-            trees += 1;
-        }
-    }
+fn parse_map(input: &Vec<&str>) -> Grid<bool> {
+    Grid::from_lines(input, |char| match char {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .unwrap()
+}
 
-    trees
+fn count_trees(map: &Grid<bool>, right: usize, bottom: usize) -> usize {
+    (1..)
+        .map(|step| (step * right, step * bottom))
+        .take_while(|&(_, y)| y < map.height())
+        .filter(|&(x, y)| *map.get_wrapping(x as isize, y as isize))
+        .count()
 }
 
 pub fn part1(input: &Vec<&str>) -> usize {
-    count_trees(input, 3, 1)
+    count_trees(&parse_map(input), 3, 1)
 }
 
 pub fn part2(input: &Vec<&str>) -> usize {
-    count_trees(input, 1, 1)
-        * count_trees(input, 3, 1)
-        * count_trees(input, 5, 1)
-        * count_trees(input, 7, 1)
-        * count_trees(input, 1, 2)
+    let map = parse_map(input);
+
+    count_trees(&map, 1, 1)
+        * count_trees(&map, 3, 1)
+        * count_trees(&map, 5, 1)
+        * count_trees(&map, 7, 1)
+        * count_trees(&map, 1, 2)
 }
 
 #[cfg(test)]
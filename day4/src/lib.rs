@@ -1,40 +1,116 @@
-use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use helpers::{SectionError, Sections};
+
+const REQUIRED_FIELDS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
+#[derive(Debug, PartialEq)]
+pub struct FieldError(String);
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected `key:value`, found \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+#[derive(Debug)]
+pub struct Passport {
+    fields: HashMap<String, String>,
+}
+
+impl FromStr for Passport {
+    type Err = FieldError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let fields = input
+            .split_whitespace()
+            .map(|field| {
+                field
+                    .split_once(':')
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .ok_or_else(|| FieldError(field.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Passport { fields })
+    }
+}
+
+fn year_between(value: &str, min: u32, max: u32) -> bool {
+    value.len() == 4 && value.parse().is_ok_and(|year| (min..=max).contains(&year))
+}
+
+fn valid_height(value: &str) -> bool {
+    let (number, min, max) = if let Some(number) = value.strip_suffix("cm") {
+        (number, 150, 193)
+    } else if let Some(number) = value.strip_suffix("in") {
+        (number, 59, 76)
+    } else {
+        return false;
+    };
+
+    number
+        .parse()
+        .is_ok_and(|number: u32| (min..=max).contains(&number))
+}
+
+impl Passport {
+    fn has_required_fields(&self) -> bool {
+        REQUIRED_FIELDS
+            .iter()
+            .all(|field| self.fields.contains_key(*field))
+    }
+
+    fn valid_field(key: &str, value: &str) -> bool {
+        match key {
+            "byr" => year_between(value, 1920, 2002),
+            "iyr" => year_between(value, 2010, 2020),
+            "eyr" => year_between(value, 2020, 2030),
+            "hgt" => valid_height(value),
+            "hcl" => value.strip_prefix('#').is_some_and(|color| {
+                color.len() == 6
+                    && color
+                        .chars()
+                        .all(|char| matches!(char, '0'..='9' | 'a'..='f'))
+            }),
+            "ecl" => ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&value),
+            "pid" => value.len() == 9 && value.chars().all(|char| char.is_ascii_digit()),
+            "cid" => true,
+            _ => false,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.has_required_fields()
+            && self
+                .fields
+                .iter()
+                .all(|(key, value)| Passport::valid_field(key, value))
+    }
+}
+
+pub fn parse_passports(input: &str) -> Result<Vec<Passport>, SectionError<FieldError>> {
+    Sections::new(input).parse_all()
+}
 
 pub fn part1(input: &str) -> usize {
-    let pattern = r"(?xm)
-    (
-        ( ( \s | \A ) cid:[^\s]+ )?
-        ( \s | \A )( byr | iyr | eyr | hgt | hcl | ecl | pid ):( [^\s]+ )
-        ( \s cid:[^\s]+ )?
-    ){7}
-    (\n|\z)
-    ";
-
-    Regex::new(pattern).unwrap().find_iter(input).count()
+    parse_passports(input)
+        .unwrap_or_else(|error| panic!("{}", error))
+        .iter()
+        .filter(|passport| passport.has_required_fields())
+        .count()
 }
 
 pub fn part2(input: &str) -> usize {
-    let pattern = r"(?xm)
-    (
-        ( ( \s | \A ) cid:[^\s]+ )?
-        ( \s | \A )(
-            ( byr:( 19[2-9][0-9] | 200[0-2] ) ) |
-            ( iyr:( 201[0-9] | 2020 ) ) |
-            ( eyr:( 202[0-9] | 2030 ) ) |
-            ( hgt:( 
-                ( 1[5-8][0-9] | 19[0-3] )cm |
-                ( 59 | 6[0-9] | 7[0-6] )in )
-            ) |
-            ( hcl:\#[0-9a-f]{6} ) |
-            ( ecl:( amb | blu | brn | gry | grn | hzl | oth ) ) |
-            ( pid:[0-9]{9} )
-        )\b
-        ( \s cid:[^\s]+ )?
-    ){7}
-    (\n|\z)
-    ";
-
-    Regex::new(pattern).unwrap().find_iter(input).count()
+    parse_passports(input)
+        .unwrap_or_else(|error| panic!("{}", error))
+        .iter()
+        .filter(|passport| passport.is_valid())
+        .count()
 }
 
 #[cfg(test)]
@@ -93,4 +169,23 @@ iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
 
         assert_eq!(part2(&input), 4);
     }
+
+    #[test]
+    fn test_extra_fields() {
+        let passport = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd byr:1937 iyr:2017 hgt:183cm";
+
+        assert_eq!(part1(&format!("{} abc:1", passport)), 1);
+        assert_eq!(part1(&format!("{}\n\n{} cid:147", passport, passport)), 2);
+        assert_eq!(part1("ecl:gry pid:860033327 eyr:2020 ecl:gry"), 0);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_passports("byr:1937 iyr:2017\n\nhcl:#fffffd pid").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "section 2, line 3: cannot parse \"hcl:#fffffd pid\": expected `key:value`, found \"pid\""
+        );
+    }
 }
@@ -1,8 +1,8 @@
-use helpers::run;
+use helpers::{load_input, run};
 
 fn main() {
-    let input1 = include_str!("../input.txt");
+    let input1 = load_input(include_str!("../input.txt"));
 
-    run("part1", || day4::part1(input1));
-    run("part2", || day4::part2(input1));
+    run("part1", || day4::part1(&input1));
+    run("part2", || day4::part2(&input1));
 }
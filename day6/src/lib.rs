@@ -1,43 +1,85 @@
-fn line_to_binary(line: &str) -> u32 {
-    line.chars()
-        .fold(0, |binary, char| binary | 1 << ((char as u8) - 97)) // 97 is the ascii code for 'a'
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use helpers::{SectionError, Sections};
+
+#[derive(Debug, PartialEq)]
+pub struct AnswerError(char);
+
+impl Display for AnswerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a question between 'a' and 'z'", self.0)
+    }
 }
 
-fn affirmative_answers(input: &str) -> usize {
-    input
-        .lines()
-        .map(line_to_binary)
-        .fold(0, |prev, binary| prev | binary)
-        .count_ones() as usize
+impl std::error::Error for AnswerError {}
+
+// One bit per question
+pub struct Person(u32);
+
+impl FromStr for Person {
+    type Err = AnswerError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        line.chars()
+            .try_fold(0, |binary, char| match char {
+                'a'..='z' => Ok(binary | 1 << ((char as u8) - b'a')),
+                _ => Err(AnswerError(char)),
+            })
+            .map(Person)
+    }
 }
 
-fn affirmative_group_answers(input: &str) -> usize {
-    input
-        .lines()
-        .map(line_to_binary)
-        .fold(u32::max_value(), |prev, binary| prev & binary)
-        .count_ones() as usize
+pub struct Group(Vec<Person>);
+
+impl Group {
+    fn affirmative_answers(&self) -> usize {
+        self.0
+            .iter()
+            .fold(0, |prev, person| prev | person.0)
+            .count_ones() as usize
+    }
+
+    fn affirmative_group_answers(&self) -> usize {
+        self.0
+            .iter()
+            .fold(u32::MAX, |prev, person| prev & person.0)
+            .count_ones() as usize
+    }
 }
 
-pub fn part1(input: &Vec<&str>) -> usize {
-    input.iter().map(|group| affirmative_answers(group)).sum()
+// People are parsed one line at a time so errors point at the failing one
+pub fn parse_groups(input: &str) -> Result<Vec<Group>, SectionError<AnswerError>> {
+    let sections = Sections::new(input);
+
+    sections
+        .iter()
+        .map(|section| sections.parse_lines(section.index).map(Group))
+        .collect()
+}
+
+pub fn part1(input: &str) -> usize {
+    parse_groups(input)
+        .unwrap_or_else(|error| panic!("{}", error))
+        .iter()
+        .map(Group::affirmative_answers)
+        .sum()
 }
 
-pub fn part2(input: &Vec<&str>) -> usize {
-    input
+pub fn part2(input: &str) -> usize {
+    parse_groups(input)
+        .unwrap_or_else(|error| panic!("{}", error))
         .iter()
-        .map(|group| affirmative_group_answers(group))
+        .map(Group::affirmative_group_answers)
         .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use helpers::split_input;
-
     use super::*;
 
-    fn input<'a>() -> Vec<&'a str> {
-        let input = "
+    fn input<'a>() -> &'a str {
+        "
 abc
 
 a
@@ -52,17 +94,26 @@ a
 a
 a
 
-b";
-        split_input(input, "\n\n")
+b"
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&input()), 11)
+        assert_eq!(part1(input()), 11)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&input()), 6)
+        assert_eq!(part2(input()), 6)
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse_groups("ab\n\nabc\na1").err().unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "section 2, line 4: cannot parse \"a1\": '1' is not a question between 'a' and 'z'"
+        );
     }
 }
@@ -1,8 +1,8 @@
-use helpers::{run, split_input};
+use helpers::{load_input, run};
 
 fn main() {
-    let input: Vec<&str> = split_input(include_str!("../input.txt"), "\n\n");
+    let raw_input = load_input(include_str!("../input.txt"));
 
-    run("part1", || day6::part1(&input));
-    run("part2", || day6::part2(&input));
+    run("part1", || day6::part1(&raw_input));
+    run("part2", || day6::part2(&raw_input));
 }
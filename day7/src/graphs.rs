@@ -1,125 +1,452 @@
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{self, Display, Formatter};
 
+use petgraph::algo::toposort;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::{EdgeRef, VisitMap, Visitable};
-use petgraph::{Directed, Graph};
+use petgraph::{Directed, Direction, Graph};
 use regex::{Captures, Regex};
 
 #[derive(Debug)]
-struct Bag<'a> {
-    color: &'a str,
+pub struct Bag {
+    color: String,
 }
 
 #[derive(Debug)]
-struct Child {
-    quantity: u8,
+pub struct Child {
+    quantity: u32,
 }
 
-type BagGraph<'a> = Graph<Bag<'a>, Child, Directed>;
+pub type BagGraph = Graph<Bag, Child, Directed>;
 
-fn parse_bags<'a>(input: &'a Vec<&str>) -> BagGraph<'a> {
-    lazy_static! {
-        static ref REGEX_BAG: Regex =
-            Regex::new(r"^([a-z]+ [a-z]+) bags contain (no other bags.)?").unwrap();
-        static ref REGEX_CHILDREN: Regex =
-            Regex::new(r"([0-9]+) ([a-z]+ [a-z]+) bags?[,.]").unwrap();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BagError {
+    Parse { line: usize, text: String },
+    UnknownColor(String),
+    // Colors of a cycle, the first one is repeated at the end
+    Cycle(Vec<String>),
+    // The bag whose contents no longer fit in a `u64`
+    Overflow(String),
+}
+
+impl Display for BagError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BagError::Parse { line, text } => {
+                write!(f, "line {}: cannot parse \"{}\"", line, text)
+            }
+            BagError::UnknownColor(color) => write!(f, "unknown color \"{}\"", color),
+            BagError::Cycle(colors) => {
+                write!(f, "the rules contain a cycle: {}", colors.join(" -> "))
+            }
+            BagError::Overflow(color) => {
+                write!(f, "too many bags inside \"{}\" to count", color)
+            }
+        }
     }
+}
 
-    let mut graph: BagGraph = Graph::new();
-    let mut nodes = HashMap::new();
+impl std::error::Error for BagError {}
 
-    for line in input.iter() {
-        let captures: Captures = REGEX_BAG.captures(line).unwrap();
-        let color = captures.get(1).unwrap().as_str();
-        let node = get_or_insert_node(&mut graph, &mut nodes, color);
+// The bag graph with an index by color. Edges go from a bag to the bags it
+// directly contains.
+#[derive(Debug)]
+pub struct BagRules {
+    graph: BagGraph,
+    nodes: HashMap<String, NodeIndex>,
+}
 
-        // Bag has children
-        if captures.get(2).is_none() {
-            for capture in REGEX_CHILDREN.captures_iter(line) {
-                let quantity = capture.get(1).unwrap().as_str().parse::<u8>().unwrap();
-                let color = capture.get(2).unwrap().as_str();
-                let child_node = get_or_insert_node(&mut graph, &mut nodes, color);
+impl BagRules {
+    pub fn parse(input: &Vec<&str>) -> Result<BagRules, BagError> {
+        lazy_static! {
+            static ref REGEX_BAG: Regex =
+                Regex::new(r"^([a-z]+ [a-z]+) bags contain (no other bags.)?").unwrap();
+            static ref REGEX_CHILDREN: Regex =
+                Regex::new(r"([0-9]+) ([a-z]+ [a-z]+) bags?[,.]").unwrap();
+        }
 
-                graph.add_edge(node, child_node, Child { quantity });
+        let mut rules = BagRules {
+            graph: Graph::new(),
+            nodes: HashMap::new(),
+        };
+
+        for (index, line) in input.iter().enumerate() {
+            let error = || BagError::Parse {
+                line: index + 1,
+                text: line.to_string(),
+            };
+
+            let captures: Captures = REGEX_BAG.captures(line).ok_or_else(error)?;
+            let color = captures.get(1).unwrap().as_str();
+            let node = rules.get_or_insert_node(color);
+
+            // Bag has children
+            if captures.get(2).is_none() {
+                for capture in REGEX_CHILDREN.captures_iter(line) {
+                    let quantity = capture
+                        .get(1)
+                        .unwrap()
+                        .as_str()
+                        .parse::<u32>()
+                        .map_err(|_| error())?;
+                    let color = capture.get(2).unwrap().as_str();
+                    let child_node = rules.get_or_insert_node(color);
+
+                    rules.graph.add_edge(node, child_node, Child { quantity });
+                }
             }
         }
-    }
 
-    graph
-}
+        Ok(rules)
+    }
 
-fn get_or_insert_node<'a>(
-    graph: &mut BagGraph<'a>,
-    nodes: &mut HashMap<&'a str, NodeIndex>,
-    color: &'a str,
-) -> NodeIndex {
-    if nodes.contains_key(color) {
-        *nodes.get(color).unwrap()
-    } else {
-        let bag = Bag { color };
-        let node = graph.add_node(bag);
+    fn get_or_insert_node(&mut self, color: &str) -> NodeIndex {
+        if let Some(&node) = self.nodes.get(color) {
+            return node;
+        }
 
-        nodes.insert(color, node);
+        let node = self.graph.add_node(Bag {
+            color: color.to_string(),
+        });
+        self.nodes.insert(color.to_string(), node);
 
         node
     }
-}
 
-fn search_node_index(graph: &BagGraph, color: &str) -> NodeIndex {
-    graph
-        .node_indices()
-        .find(|node| color == graph[*node].color)
-        .unwrap()
-}
+    pub fn graph(&self) -> &BagGraph {
+        &self.graph
+    }
 
-pub fn part1(input: &Vec<&str>) -> u32 {
-    let mut graph = parse_bags(input);
-    graph.reverse();
+    fn node(&self, color: &str) -> Result<NodeIndex, BagError> {
+        self.nodes
+            .get(color)
+            .copied()
+            .ok_or_else(|| BagError::UnknownColor(color.to_string()))
+    }
+
+    fn colors(&self, nodes: &[NodeIndex]) -> Vec<&str> {
+        nodes
+            .iter()
+            .map(|&node| self.graph[node].color.as_str())
+            .collect()
+    }
+
+    // Breadth first search down the containment edges, `None` when `to` is
+    // not reached
+    fn shortest(&self, from: NodeIndex, to: NodeIndex) -> Option<Vec<NodeIndex>> {
+        let mut previous: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut pending = VecDeque::new();
+        pending.push_back(from);
+
+        while let Some(node) = pending.pop_front() {
+            for next in self.graph.neighbors(node) {
+                if next == to {
+                    let mut path = vec![to, node];
+
+                    while let Some(&node) = previous.get(path.last().unwrap()) {
+                        path.push(node);
+                    }
+
+                    path.reverse();
+                    return Some(path);
+                }
+
+                if next != from && !previous.contains_key(&next) {
+                    previous.insert(next, node);
+                    pending.push_back(next);
+                }
+            }
+        }
 
-    let mut visited = graph.visit_map();
-    let mut count = 0;
-    let mut visit_next = BinaryHeap::new();
+        None
+    }
+
+    // Some cycle of the graph, as colors with the first one repeated at the end
+    pub fn cycle(&self) -> Option<Vec<&str>> {
+        let node = toposort(&self.graph, None).err()?.node_id();
 
-    let initial_node = search_node_index(&graph, "shiny gold");
-    visit_next.push(initial_node);
+        self.shortest(node, node).map(|path| self.colors(&path))
+    }
 
-    while let Some(node) = visit_next.pop() {
-        if visited.is_visited(&node) {
-            continue;
+    // `from` and every bag inside it, each bag before the bags it contains.
+    // Only a cycle below `from` is an error, found when the depth first search
+    // reaches a bag that is still on its path.
+    fn topological_order(&self, from: NodeIndex) -> Result<Vec<NodeIndex>, BagError> {
+        let mut order = Vec::new();
+        let mut done: HashSet<NodeIndex> = HashSet::new();
+        let mut path = vec![from];
+        let mut pending = vec![self.graph.neighbors(from)];
+
+        while let Some(children) = pending.last_mut() {
+            match children.next() {
+                Some(next) if path.contains(&next) => {
+                    let start = path.iter().position(|&node| node == next).unwrap();
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(next);
+
+                    return Err(BagError::Cycle(
+                        self.colors(&cycle)
+                            .iter()
+                            .map(|color| color.to_string())
+                            .collect(),
+                    ));
+                }
+                Some(next) if done.contains(&next) => {}
+                Some(next) => {
+                    path.push(next);
+                    pending.push(self.graph.neighbors(next));
+                }
+                None => {
+                    let node = path.pop().unwrap();
+                    pending.pop();
+                    done.insert(node);
+                    order.push(node);
+                }
+            }
         }
 
-        visited.visit(node);
-        count += 1;
+        order.reverse();
+        Ok(order)
+    }
 
-        for edge in graph.edges(node) {
-            let next = edge.target();
-            visit_next.push(next);
+    // Every bag that eventually contains `color`, sorted
+    pub fn containers(&self, color: &str) -> Result<Vec<&str>, BagError> {
+        let start = self.node(color)?;
+        let mut visited = self.graph.visit_map();
+        let mut pending = vec![start];
+        let mut containers = Vec::new();
+
+        while let Some(node) = pending.pop() {
+            // Walk containment backwards, from the bag to its parents
+            for next in self.graph.neighbors_directed(node, Direction::Incoming) {
+                if visited.visit(next) {
+                    containers.push(self.graph[next].color.as_str());
+                    pending.push(next);
+                }
+            }
         }
+
+        containers.sort_unstable();
+        Ok(containers)
     }
 
-    count - 1
-}
+    // How many bags end up inside one `color` bag. Every bag below it is
+    // counted once, children before parents, so shared bags cost nothing extra.
+    // Rules outside `color` are never looked at, cycles included.
+    pub fn contents(&self, color: &str) -> Result<u64, BagError> {
+        let start = self.node(color)?;
+        let order = self.topological_order(start)?;
 
-pub fn part2(input: &Vec<&str>) -> u32 {
-    let graph = parse_bags(input);
+        let mut counts: HashMap<NodeIndex, u64> = HashMap::new();
 
-    let mut count = 0;
-    let mut visit_next = BinaryHeap::new();
+        for &node in order.iter().rev() {
+            let mut count: u64 = 0;
 
-    let initial_node = search_node_index(&graph, "shiny gold");
-    visit_next.push((1, initial_node));
+            for edge in self.graph.edges(node) {
+                let inside = counts[&edge.target()];
+                let times = inside
+                    .checked_add(1)
+                    .and_then(|bags| bags.checked_mul(edge.weight().quantity as u64))
+                    .and_then(|bags| bags.checked_add(count));
 
-    while let Some((multiplier, node)) = visit_next.pop() {
-        for edge in graph.edges(node) {
-            let next = edge.target();
-            let times = multiplier * edge.weight().quantity as u32;
-            visit_next.push((times, next));
+                count =
+                    times.ok_or_else(|| BagError::Overflow(self.graph[node].color.to_string()))?;
+            }
+
+            counts.insert(node, count);
+        }
+
+        Ok(counts[&start])
+    }
+
+    // The fewest nested bags from `outer` down to `inner`, both included
+    pub fn shortest_path(&self, outer: &str, inner: &str) -> Result<Option<Vec<&str>>, BagError> {
+        let (from, to) = (self.node(outer)?, self.node(inner)?);
 
-            count += times;
+        if from == to {
+            return Ok(Some(vec![self.graph[from].color.as_str()]));
         }
+
+        Ok(self.shortest(from, to).map(|path| self.colors(&path)))
     }
 
-    count
+    // The most nested bags from `outer` down to `inner`, only defined when
+    // there is no cycle inside `outer`
+    pub fn longest_path(&self, outer: &str, inner: &str) -> Result<Option<Vec<&str>>, BagError> {
+        let (from, to) = (self.node(outer)?, self.node(inner)?);
+        let order = self.topological_order(from)?;
+
+        let mut length: HashMap<NodeIndex, usize> = HashMap::new();
+        let mut previous: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        length.insert(from, 0);
+
+        for node in order {
+            let current = match length.get(&node) {
+                Some(&current) => current,
+                None => continue,
+            };
+
+            for next in self.graph.neighbors(node) {
+                if length.get(&next).is_none_or(|&other| other < current + 1) {
+                    length.insert(next, current + 1);
+                    previous.insert(next, node);
+                }
+            }
+        }
+
+        if !length.contains_key(&to) {
+            return Ok(None);
+        }
+
+        let mut path = vec![to];
+
+        while let Some(&node) = previous.get(path.last().unwrap()) {
+            path.push(node);
+        }
+
+        path.reverse();
+        Ok(Some(self.colors(&path)))
+    }
+
+    // `color` and every bag inside it
+    fn reachable(&self, color: &str) -> Result<HashSet<NodeIndex>, BagError> {
+        let start = self.node(color)?;
+        let mut reachable: HashSet<NodeIndex> = HashSet::new();
+        let mut pending = vec![start];
+        reachable.insert(start);
+
+        while let Some(node) = pending.pop() {
+            for next in self.graph.neighbors(node) {
+                if reachable.insert(next) {
+                    pending.push(next);
+                }
+            }
+        }
+
+        Ok(reachable)
+    }
+
+    // Edges of a bag in the order of its rule
+    fn children(&self, node: NodeIndex) -> Vec<(NodeIndex, &Child)> {
+        let mut edges: Vec<_> = self.graph.edges(node).collect();
+        edges.sort_by_key(|edge| edge.id());
+
+        edges
+            .iter()
+            .map(|edge| (edge.target(), edge.weight()))
+            .collect()
+    }
+
+    // Graphviz source with quantities as edge labels. With `highlight`, that
+    // bag and everything inside it are drawn in red.
+    pub fn to_dot(&self, highlight: Option<&str>) -> Result<String, BagError> {
+        let reachable = match highlight {
+            Some(color) => self.reachable(color)?,
+            None => HashSet::new(),
+        };
+        let start = highlight.map(|color| self.node(color)).transpose()?;
+
+        let mut output = String::from("digraph bags {\n    node [shape=box];\n");
+
+        for node in self.graph.node_indices() {
+            let attributes = if Some(node) == start {
+                " [color=red, penwidth=2, style=filled, fillcolor=mistyrose]"
+            } else if reachable.contains(&node) {
+                " [color=red, penwidth=2]"
+            } else {
+                ""
+            };
+
+            output.push_str(&format!(
+                "    {}{};\n",
+                quote(&self.graph[node].color),
+                attributes
+            ));
+        }
+
+        for node in self.graph.node_indices() {
+            for (target, child) in self.children(node) {
+                let highlighted = if reachable.contains(&node) {
+                    ", color=red, penwidth=2"
+                } else {
+                    ""
+                };
+
+                output.push_str(&format!(
+                    "    {} -> {} [label={}{}];\n",
+                    quote(&self.graph[node].color),
+                    quote(&self.graph[target].color),
+                    child.quantity,
+                    highlighted
+                ));
+            }
+        }
+
+        output.push_str("}\n");
+        Ok(output)
+    }
+
+    // An object from every color to the bags it directly contains
+    pub fn to_json(&self) -> String {
+        let bags: Vec<String> = self
+            .graph
+            .node_indices()
+            .map(|node| {
+                let children: Vec<String> = self
+                    .children(node)
+                    .iter()
+                    .map(|(target, child)| {
+                        format!(
+                            "    {{\"color\": {}, \"quantity\": {}}}",
+                            quote(&self.graph[*target].color),
+                            child.quantity
+                        )
+                    })
+                    .collect();
+
+                match children.len() {
+                    0 => format!("  {}: []", quote(&self.graph[node].color)),
+                    _ => format!(
+                        "  {}: [\n{}\n  ]",
+                        quote(&self.graph[node].color),
+                        children.join(",\n")
+                    ),
+                }
+            })
+            .collect();
+
+        format!("{{\n{}\n}}\n", bags.join(",\n"))
+    }
+}
+
+// A double quoted string, valid in both DOT and JSON
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn parse_bags(input: &Vec<&str>) -> BagRules {
+    BagRules::parse(input).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn part1(input: &Vec<&str>) -> u32 {
+    part1_parsed(&parse_bags(input))
+}
+
+pub fn part1_parsed(rules: &BagRules) -> u32 {
+    rules
+        .containers("shiny gold")
+        .unwrap_or_else(|error| panic!("{}", error))
+        .len() as u32
+}
+
+pub fn part2(input: &Vec<&str>) -> u64 {
+    part2_parsed(&parse_bags(input))
+}
+
+pub fn part2_parsed(rules: &BagRules) -> u64 {
+    rules
+        .contents("shiny gold")
+        .unwrap_or_else(|error| panic!("{}", error))
 }
 
 #[cfg(test)]
@@ -128,9 +455,7 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_part1() {
-        let input = "
+    const RULES: &str = "
 light red bags contain 1 bright white bag, 2 muted yellow bags.
 dark orange bags contain 3 bright white bags, 4 muted yellow bags.
 bright white bags contain 1 shiny gold bag.
@@ -142,7 +467,53 @@ faded blue bags contain no other bags.
 dotted black bags contain no other bags.
 ";
 
-        assert_eq!(part1(&input_lines(input)), 4);
+    const CYCLE: &str = "
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 1 dark orange bag, 3 faded blue bags.
+dark orange bags contain 1 shiny gold bag.
+faded blue bags contain no other bags.
+";
+
+    // Bag `n` contains `quantities[k]` of bag `n + 1 + k`, when it exists
+    fn generated(colors: usize, quantities: &[u32]) -> Vec<String> {
+        (0..colors)
+            .map(|bag| {
+                let children: Vec<String> = quantities
+                    .iter()
+                    .enumerate()
+                    .filter(|(offset, _)| bag + 1 + offset < colors)
+                    .map(|(offset, quantity)| {
+                        format!("{} {} bags", quantity, color(bag + 1 + offset))
+                    })
+                    .collect();
+
+                match children.len() {
+                    0 => format!("{} bags contain no other bags.", color(bag)),
+                    _ => format!("{} bags contain {}.", color(bag), children.join(", ")),
+                }
+            })
+            .collect()
+    }
+
+    // Two words of lowercase letters, like the puzzle colors
+    fn color(mut number: usize) -> String {
+        let mut letters = Vec::new();
+
+        loop {
+            letters.push((b'a' + (number % 26) as u8) as char);
+            number /= 26;
+
+            if 0 == number {
+                break;
+            }
+        }
+
+        format!("shade {}", letters.iter().rev().collect::<String>())
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&input_lines(RULES)), 4);
     }
 
     #[test]
@@ -159,4 +530,211 @@ dark violet bags contain no other bags.
 
         assert_eq!(part2(&input_lines(input)), 126);
     }
+
+    #[test]
+    fn test_queries() {
+        let input = input_lines(RULES);
+        let rules = BagRules::parse(&input).unwrap();
+
+        assert_eq!(
+            rules.containers("muted yellow"),
+            Ok(vec!["dark orange", "light red"])
+        );
+        assert_eq!(rules.contents("dark olive"), Ok(7));
+        assert_eq!(rules.contents("faded blue"), Ok(0));
+        assert_eq!(
+            rules.shortest_path("light red", "faded blue"),
+            Ok(Some(vec!["light red", "muted yellow", "faded blue"]))
+        );
+        // Through either dark olive or vibrant plum
+        assert_eq!(
+            rules
+                .longest_path("light red", "faded blue")
+                .map(|path| path.map(|path| path.len())),
+            Ok(Some(5))
+        );
+        assert_eq!(rules.shortest_path("faded blue", "light red"), Ok(None));
+        assert_eq!(rules.cycle(), None);
+        assert_eq!(
+            rules.containers("pale green"),
+            Err(BagError::UnknownColor("pale green".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_cycle() {
+        let input = input_lines(CYCLE);
+        let rules = BagRules::parse(&input).unwrap();
+
+        assert_eq!(rules.cycle().map(|cycle| cycle.len()), Some(4));
+        assert_eq!(
+            rules.contents("shiny gold").unwrap_err().to_string(),
+            "the rules contain a cycle: shiny gold -> dark red -> dark orange -> shiny gold"
+        );
+        assert_eq!(
+            rules.longest_path("dark red", "faded blue"),
+            Err(BagError::Cycle(
+                ["dark red", "dark orange", "shiny gold", "dark red"]
+                    .iter()
+                    .map(|color| color.to_string())
+                    .collect()
+            ))
+        );
+        assert_eq!(
+            rules.shortest_path("dark red", "shiny gold"),
+            Ok(Some(vec!["dark red", "dark orange", "shiny gold"]))
+        );
+        assert_eq!(
+            rules.containers("faded blue"),
+            Ok(vec!["dark orange", "dark red", "shiny gold"])
+        );
+    }
+
+    #[test]
+    fn test_unrelated_cycle() {
+        let input = vec![
+            "light red bags contain 1 bright white bag, 2 muted yellow bags.",
+            "bright white bags contain 1 muted yellow bag.",
+            "muted yellow bags contain no other bags.",
+            "dark orange bags contain 1 dotted black bag.",
+            "dotted black bags contain 1 dark orange bag, 1 muted yellow bag.",
+        ];
+        let rules = BagRules::parse(&input).unwrap();
+
+        assert!(rules.cycle().is_some());
+        assert_eq!(rules.contents("light red"), Ok(4));
+        assert_eq!(
+            rules.contents("dotted black"),
+            Err(BagError::Cycle(
+                ["dotted black", "dark orange", "dotted black"]
+                    .iter()
+                    .map(|color| color.to_string())
+                    .collect()
+            ))
+        );
+        assert_eq!(
+            rules.longest_path("light red", "muted yellow"),
+            Ok(Some(vec!["light red", "bright white", "muted yellow"]))
+        );
+        assert!(rules.longest_path("dark orange", "muted yellow").is_err());
+    }
+
+    #[test]
+    fn test_dot() {
+        let input = vec![
+            "shiny gold bags contain 2 dark red bags, 1 faded blue bag.",
+            "dark red bags contain 12 faded blue bags.",
+            "light red bags contain 1 shiny gold bag.",
+        ];
+        let rules = BagRules::parse(&input).unwrap();
+
+        assert_eq!(
+            rules.to_dot(None).unwrap(),
+            r#"digraph bags {
+    node [shape=box];
+    "shiny gold";
+    "dark red";
+    "faded blue";
+    "light red";
+    "shiny gold" -> "dark red" [label=2];
+    "shiny gold" -> "faded blue" [label=1];
+    "dark red" -> "faded blue" [label=12];
+    "light red" -> "shiny gold" [label=1];
+}
+"#
+        );
+        assert_eq!(
+            rules.to_dot(Some("dark red")).unwrap(),
+            r#"digraph bags {
+    node [shape=box];
+    "shiny gold";
+    "dark red" [color=red, penwidth=2, style=filled, fillcolor=mistyrose];
+    "faded blue" [color=red, penwidth=2];
+    "light red";
+    "shiny gold" -> "dark red" [label=2];
+    "shiny gold" -> "faded blue" [label=1];
+    "dark red" -> "faded blue" [label=12, color=red, penwidth=2];
+    "light red" -> "shiny gold" [label=1];
+}
+"#
+        );
+        assert!(rules.to_dot(Some("pale green")).is_err());
+    }
+
+    #[test]
+    fn test_json() {
+        let input = vec![
+            "shiny gold bags contain 2 dark red bags, 1 faded blue bag.",
+            "faded blue bags contain no other bags.",
+        ];
+        let rules = BagRules::parse(&input).unwrap();
+
+        assert_eq!(
+            rules.to_json(),
+            r#"{
+  "shiny gold": [
+    {"color": "dark red", "quantity": 2},
+    {"color": "faded blue", "quantity": 1}
+  ],
+  "dark red": [],
+  "faded blue": []
+}
+"#
+        );
+        assert_eq!(quote("say \"hi\" \\o/"), r#""say \"hi\" \\o/""#);
+    }
+
+    #[test]
+    fn test_large_quantities() {
+        let input = vec![
+            "shiny gold bags contain 300 dark red bags.",
+            "dark red bags contain 1000 faded blue bags.",
+        ];
+        let rules = BagRules::parse(&input).unwrap();
+
+        assert_eq!(rules.contents("shiny gold"), Ok(300_300));
+        assert!(rules.to_json().contains("\"quantity\": 1000"));
+
+        let input = vec!["shiny gold bags contain 5000000000 dark red bags."];
+        assert!(BagRules::parse(&input).is_err());
+    }
+
+    #[test]
+    fn test_generated_rules() {
+        // Every bag shares its children with its neighbours, so the number of
+        // paths doubles every couple of levels
+        let lines = generated(10_000, &[1, 2]);
+        let input: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let rules = BagRules::parse(&input).unwrap();
+
+        let mut expected: Vec<u128> = vec![0, 1];
+        while expected.last().unwrap() <= &(u64::MAX as u128) {
+            let length = expected.len();
+            expected.push(3 + expected[length - 1] + 2 * expected[length - 2]);
+        }
+
+        let deepest = expected.len() - 2;
+        assert_eq!(
+            rules.contents(&color(9_999 - deepest)),
+            Ok(expected[deepest] as u64)
+        );
+        assert_eq!(
+            rules.contents(&color(0)),
+            Err(BagError::Overflow(color(9_999 - deepest - 1)))
+        );
+        assert_eq!(rules.containers(&color(9_999)).unwrap().len(), 9_999);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = vec![
+            "light red bags contain 1 bright white bag.",
+            "light red bags",
+        ];
+
+        assert_eq!(
+            BagRules::parse(&input).unwrap_err().to_string(),
+            "line 2: cannot parse \"light red bags\""
+        );
+    }
 }
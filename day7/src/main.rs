@@ -1,11 +1,87 @@
-use helpers::{input_lines, run};
+use std::env;
+use std::fs;
+use std::process;
+
+use day7::graphs::BagRules;
+use helpers::{input_lines, load_input, run, run_parsed, run_with_parse, InputSource, INPUT_ENV};
+
+const USAGE: &str = "usage:
+    day7 [input file or - for stdin]
+    day7 export [--dot FILE] [--json FILE] [--highlight COLOR] [input file or - for stdin]";
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn take_option<'a>(args: &mut Vec<&'a str>, name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|&arg| arg == name)?;
+
+    if args.len() <= index + 1 {
+        exit_with(USAGE);
+    }
+
+    args.drain(index..=index + 1).nth(1)
+}
+
+// Writes the bag graph as Graphviz and/or JSON files
+fn export(mut args: Vec<&str>) {
+    let dot = take_option(&mut args, "--dot");
+    let json = take_option(&mut args, "--json");
+    let highlight = take_option(&mut args, "--highlight");
+
+    if args.len() > 1 || (dot.is_none() && json.is_none()) {
+        exit_with(USAGE);
+    }
+
+    let variable = env::var(INPUT_ENV).ok();
+    let raw_input = InputSource::select(
+        args.first().copied(),
+        variable.as_deref(),
+        include_str!("../input.txt"),
+    )
+    .read()
+    .unwrap_or_else(|error| exit_with(&error.to_string()));
+    let input: Vec<&str> = input_lines(&raw_input);
+    let rules = BagRules::parse(&input).unwrap_or_else(|error| exit_with(&error.to_string()));
+
+    let write = |path: &str, contents: String| {
+        fs::write(path, contents).unwrap_or_else(|error| exit_with(&error.to_string()));
+        println!("wrote {}", path);
+    };
+
+    if let Some(path) = dot {
+        let contents = rules
+            .to_dot(highlight)
+            .unwrap_or_else(|error| exit_with(&error.to_string()));
+        write(path, contents);
+    }
+
+    if let Some(path) = json {
+        write(path, rules.to_json());
+    }
+}
 
 fn main() {
-    let input: Vec<&str> = input_lines(include_str!("../input.txt"));
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["export", rest @ ..] => return export(rest.to_vec()),
+        ["--help"] | ["-h"] => exit_with(USAGE),
+        _ => {}
+    }
+
+    let raw_input = load_input(include_str!("../input.txt"));
+    let input: Vec<&str> = input_lines(&raw_input);
 
     run("part1", || day7::part1(&input));
     run("part2", || day7::part2(&input));
 
-    run("graphs::part1", || day7::graphs::part1(&input));
-    run("graphs::part2", || day7::graphs::part2(&input));
+    let graph = run_with_parse(
+        "graphs::part1",
+        || day7::graphs::parse_bags(&input),
+        day7::graphs::part1_parsed,
+    );
+    run_parsed("graphs::part2", &graph, day7::graphs::part2_parsed);
 }
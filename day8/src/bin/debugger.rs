@@ -0,0 +1,58 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use day8::debugger::{Command, Debugger};
+use day8::vm::parse_program;
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or_else(|| {
+        eprintln!("usage: debugger <program file>");
+        process::exit(2)
+    });
+    let input = fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("{}: {}", path, error);
+        process::exit(2)
+    });
+    let instructions = parse_program(&input).unwrap_or_else(|error| {
+        eprintln!("{}: {}", path, error);
+        process::exit(2)
+    });
+
+    let mut debugger = Debugger::new(instructions);
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        write!(output, "(day8) ").expect("Something went wrong writing the prompt!");
+        output
+            .flush()
+            .expect("Something went wrong writing the prompt!");
+
+        let line = match lines.next() {
+            Some(line) => line.expect("Something went wrong reading the command!"),
+            None => break,
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let running = match line.parse::<Command>() {
+            Ok(command) => debugger
+                .execute(command, &mut output)
+                .expect("Something went wrong writing the output!"),
+            Err(error) => {
+                writeln!(output, "{}", error).expect("Something went wrong writing the output!");
+                true
+            }
+        };
+
+        if !running {
+            break;
+        }
+    }
+}
@@ -0,0 +1,568 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::vm::{disassemble, Halt, Instruction, Interpreter, State};
+
+pub const HELP: &str = "commands:
+    s, step [N]              execute N instructions (1 by default)
+    c, continue              run until a breakpoint, a halt or a repeated instruction
+    b, break <pc>            stop before executing the instruction at pc
+    b, break acc <op> <N>    stop when the accumulator compares to N, op is one of == != < <= > >=
+    d, delete <N>            remove the Nth breakpoint
+    w, watch                 toggle printing every accumulator change
+    r, rewind [N]            go back N instructions (1 by default)
+    i, info                  show the state and the breakpoints
+    l, list                  disassemble the program
+    reset                    start over, keeping breakpoints
+    h, help                  show this help
+    q, quit                  leave the debugger";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn matches(&self, left: i32, right: i32) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        };
+
+        write!(f, "{}", operator)
+    }
+}
+
+impl FromStr for Comparison {
+    type Err = CommandError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "==" => Ok(Comparison::Equal),
+            "!=" => Ok(Comparison::NotEqual),
+            "<" => Ok(Comparison::Less),
+            "<=" => Ok(Comparison::LessOrEqual),
+            ">" => Ok(Comparison::Greater),
+            ">=" => Ok(Comparison::GreaterOrEqual),
+            _ => Err(CommandError::Invalid(input.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    Pointer(usize),
+    Accumulator(Comparison, i32),
+}
+
+impl Breakpoint {
+    fn matches(&self, state: &State) -> bool {
+        match self {
+            Breakpoint::Pointer(pointer) => state.pointer == *pointer,
+            Breakpoint::Accumulator(comparison, value) => {
+                comparison.matches(state.accumulator, *value)
+            }
+        }
+    }
+}
+
+impl Display for Breakpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Pointer(pointer) => write!(f, "pc == {}", pointer),
+            Breakpoint::Accumulator(comparison, value) => {
+                write!(f, "acc {} {}", comparison, value)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Command {
+    Step(usize),
+    Continue,
+    Break(Breakpoint),
+    Delete(usize),
+    Watch,
+    Rewind(usize),
+    Info,
+    List,
+    Reset,
+    Help,
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    Unknown(String),
+    Invalid(String),
+    Missing,
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Unknown(command) => {
+                write!(f, "unknown command \"{}\", try \"help\"", command)
+            }
+            CommandError::Invalid(argument) => write!(f, "invalid argument \"{}\"", argument),
+            CommandError::Missing => write!(f, "missing argument, try \"help\""),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+fn number<T: FromStr>(argument: Option<&str>, default: Option<T>) -> Result<T, CommandError> {
+    match argument {
+        Some(argument) => argument
+            .parse()
+            .map_err(|_| CommandError::Invalid(argument.to_string())),
+        None => default.ok_or(CommandError::Missing),
+    }
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut words = input.split_whitespace();
+        let name = words.next().ok_or(CommandError::Missing)?;
+        let argument = words.next();
+
+        let command = match name {
+            "s" | "step" => Command::Step(number(argument, Some(1))?),
+            "c" | "continue" => Command::Continue,
+            "b" | "break" => match argument {
+                Some("acc") => {
+                    let comparison = words.next().ok_or(CommandError::Missing)?.parse()?;
+                    let value = number(words.next(), None)?;
+
+                    Command::Break(Breakpoint::Accumulator(comparison, value))
+                }
+                argument => Command::Break(Breakpoint::Pointer(number(argument, None)?)),
+            },
+            "d" | "delete" => Command::Delete(number(argument, None)?),
+            "w" | "watch" => Command::Watch,
+            "r" | "rewind" => Command::Rewind(number(argument, Some(1))?),
+            "i" | "info" => Command::Info,
+            "l" | "list" => Command::List,
+            "reset" => Command::Reset,
+            "h" | "help" => Command::Help,
+            "q" | "quit" => Command::Quit,
+            _ => return Err(CommandError::Unknown(name.to_string())),
+        };
+
+        match words.next() {
+            Some(extra) => Err(CommandError::Invalid(extra.to_string())),
+            None => Ok(command),
+        }
+    }
+}
+
+// Why a step or a continue stopped early
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Stop {
+    Halt(Halt),
+    Breakpoint(usize),
+}
+
+pub struct Debugger {
+    interpreter: Interpreter,
+    history: Vec<State>,
+    breakpoints: Vec<Breakpoint>,
+    watch: bool,
+    // Stopped at a breakpoint that must not stop the next instruction again
+    paused: bool,
+}
+
+impl Debugger {
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Debugger {
+            interpreter: Interpreter::new(instructions),
+            history: Vec::new(),
+            breakpoints: Vec::new(),
+            watch: false,
+            paused: false,
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.interpreter.state()
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    // Breakpoints stop before the instruction at the pointer runs
+    fn breakpoint(&mut self) -> Option<usize> {
+        if self.paused {
+            return None;
+        }
+
+        let state = self.interpreter.state();
+        let index = self
+            .breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.matches(&state))?;
+        self.paused = true;
+
+        Some(index)
+    }
+
+    fn step(&mut self, output: &mut dyn Write, quiet: bool) -> io::Result<Result<(), Stop>> {
+        let before = self.interpreter.state();
+        let entry = match self.interpreter.step() {
+            Ok(entry) => entry,
+            Err(halt) => return Ok(Err(Stop::Halt(halt))),
+        };
+        self.history.push(before);
+        self.paused = false;
+
+        if !quiet {
+            writeln!(output, "{}", entry)?;
+        }
+
+        if self.watch && before.accumulator != entry.state.accumulator {
+            writeln!(
+                output,
+                "watch: acc {} -> {} at {}",
+                before.accumulator, entry.state.accumulator, entry.pointer
+            )?;
+        }
+
+        Ok(Ok(()))
+    }
+
+    fn report(&self, output: &mut dyn Write, stop: Stop) -> io::Result<()> {
+        let state = self.interpreter.state();
+
+        match stop {
+            Stop::Halt(halt) => writeln!(output, "halted: {} (acc={})", halt, state.accumulator),
+            Stop::Breakpoint(index) => writeln!(
+                output,
+                "breakpoint {}: {} (pc={} acc={})",
+                index + 1,
+                self.breakpoints[index],
+                state.pointer,
+                state.accumulator
+            ),
+        }
+    }
+
+    // Returns false once the user asks to quit
+    pub fn execute(&mut self, command: Command, output: &mut dyn Write) -> io::Result<bool> {
+        match command {
+            // The first instruction runs even on a breakpoint, the user asked for it
+            Command::Step(count) => {
+                for index in 0..count {
+                    let breakpoint = match index {
+                        0 => None,
+                        _ => self.breakpoint(),
+                    };
+
+                    if let Some(index) = breakpoint {
+                        self.report(output, Stop::Breakpoint(index))?;
+                        break;
+                    }
+
+                    if let Err(stop) = self.step(output, false)? {
+                        self.report(output, stop)?;
+                        break;
+                    }
+                }
+            }
+            Command::Continue => {
+                let mut visited: HashSet<usize> = HashSet::new();
+
+                loop {
+                    let pointer = self.interpreter.pointer();
+
+                    if let Some(index) = self.breakpoint() {
+                        self.report(output, Stop::Breakpoint(index))?;
+                        break;
+                    }
+
+                    if !visited.insert(pointer) {
+                        self.report(output, Stop::Halt(Halt::InfiniteLoop { pointer }))?;
+                        break;
+                    }
+
+                    if let Err(stop) = self.step(output, true)? {
+                        self.report(output, stop)?;
+                        break;
+                    }
+                }
+            }
+            Command::Break(breakpoint) => {
+                self.breakpoints.push(breakpoint);
+                writeln!(
+                    output,
+                    "breakpoint {}: {}",
+                    self.breakpoints.len(),
+                    breakpoint
+                )?;
+            }
+            Command::Delete(number) => {
+                if number == 0 || self.breakpoints.len() < number {
+                    writeln!(output, "no breakpoint {}", number)?;
+                } else {
+                    let breakpoint = self.breakpoints.remove(number - 1);
+                    writeln!(output, "deleted breakpoint {}: {}", number, breakpoint)?;
+                }
+            }
+            Command::Watch => {
+                self.watch = !self.watch;
+                writeln!(
+                    output,
+                    "watch {}",
+                    if self.watch { "enabled" } else { "disabled" }
+                )?;
+            }
+            Command::Rewind(count) => {
+                let count = count.min(self.history.len());
+                let index = self.history.len() - count;
+
+                if let Some(&state) = self.history.get(index) {
+                    self.interpreter.set_state(state);
+                    self.paused = false;
+                }
+                self.history.truncate(index);
+
+                let state = self.interpreter.state();
+                writeln!(
+                    output,
+                    "rewound {} (pc={} acc={})",
+                    count, state.pointer, state.accumulator
+                )?;
+            }
+            Command::Info => {
+                let state = self.interpreter.state();
+                writeln!(
+                    output,
+                    "pc={} acc={} steps={}",
+                    state.pointer,
+                    state.accumulator,
+                    self.history.len()
+                )?;
+
+                for (index, breakpoint) in self.breakpoints.iter().enumerate() {
+                    writeln!(output, "breakpoint {}: {}", index + 1, breakpoint)?;
+                }
+            }
+            Command::List => {
+                let pointer = self.interpreter.pointer();
+
+                for (index, line) in disassemble(self.interpreter.instructions())
+                    .lines()
+                    .enumerate()
+                {
+                    let marker = if index == pointer { "=>" } else { "  " };
+                    writeln!(output, "{} {}", marker, line)?;
+                }
+            }
+            Command::Reset => {
+                self.interpreter.reset();
+                self.history.clear();
+                self.paused = false;
+                writeln!(output, "reset")?;
+            }
+            Command::Help => writeln!(output, "{}", HELP)?,
+            Command::Quit => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::parse_program;
+
+    use super::*;
+
+    fn debugger() -> Debugger {
+        Debugger::new(
+            parse_program(
+                "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6",
+            )
+            .unwrap(),
+        )
+    }
+
+    fn execute(debugger: &mut Debugger, command: &str) -> String {
+        let mut output = Vec::new();
+        debugger
+            .execute(command.parse().unwrap(), &mut output)
+            .unwrap();
+
+        String::from_utf8(output).expect("Not UTF-8")
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!("s".parse(), Ok(Command::Step(1)));
+        assert_eq!("step 3".parse(), Ok(Command::Step(3)));
+        assert_eq!("b 4".parse(), Ok(Command::Break(Breakpoint::Pointer(4))));
+        assert_eq!(
+            "break acc >= -2".parse(),
+            Ok(Command::Break(Breakpoint::Accumulator(
+                Comparison::GreaterOrEqual,
+                -2
+            )))
+        );
+        assert_eq!(
+            "break acc ~ 2".parse::<Command>(),
+            Err(CommandError::Invalid("~".to_string()))
+        );
+        assert_eq!("b".parse::<Command>(), Err(CommandError::Missing));
+        assert_eq!(
+            "jump".parse::<Command>(),
+            Err(CommandError::Unknown("jump".to_string()))
+        );
+        assert_eq!(
+            "step 1 2".parse::<Command>(),
+            Err(CommandError::Invalid("2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_step_and_rewind() {
+        let mut debugger = debugger();
+
+        assert_eq!(
+            execute(&mut debugger, "step 2"),
+            "    0  nop +0   pc=1 acc=0\n    1  acc +1   pc=2 acc=1\n"
+        );
+        assert_eq!(execute(&mut debugger, "rewind"), "rewound 1 (pc=1 acc=0)\n");
+        assert_eq!(
+            execute(&mut debugger, "rewind 5"),
+            "rewound 1 (pc=0 acc=0)\n"
+        );
+        assert_eq!(debugger.state(), State::default());
+    }
+
+    #[test]
+    fn test_continue_until_loop() {
+        let mut debugger = debugger();
+
+        assert_eq!(
+            execute(&mut debugger, "c"),
+            "halted: infinite loop at 1 (acc=5)\n"
+        );
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut debugger = debugger();
+        execute(&mut debugger, "break 6");
+        execute(&mut debugger, "break acc > 3");
+
+        assert_eq!(
+            execute(&mut debugger, "continue"),
+            "breakpoint 1: pc == 6 (pc=6 acc=1)\n"
+        );
+        assert_eq!(
+            execute(&mut debugger, "continue"),
+            "breakpoint 2: acc > 3 (pc=4 acc=5)\n"
+        );
+        assert_eq!(
+            execute(&mut debugger, "delete 1"),
+            "deleted breakpoint 1: pc == 6\n"
+        );
+        assert_eq!(debugger.breakpoints().len(), 1);
+    }
+
+    #[test]
+    fn test_breakpoint_before_instruction() {
+        let mut debugger = debugger();
+        execute(&mut debugger, "step 3");
+        execute(&mut debugger, "break 0");
+        execute(&mut debugger, "reset");
+
+        assert_eq!(
+            execute(&mut debugger, "continue"),
+            "breakpoint 1: pc == 0 (pc=0 acc=0)\n"
+        );
+        assert_eq!(
+            execute(&mut debugger, "step"),
+            "    0  nop +0   pc=1 acc=0\n"
+        );
+
+        // The loop head stops every time around, not once per continue
+        execute(&mut debugger, "delete 1");
+        execute(&mut debugger, "break 1");
+
+        assert_eq!(
+            execute(&mut debugger, "continue"),
+            "breakpoint 1: pc == 1 (pc=1 acc=0)\n"
+        );
+        assert_eq!(
+            execute(&mut debugger, "continue"),
+            "breakpoint 1: pc == 1 (pc=1 acc=5)\n"
+        );
+
+        execute(&mut debugger, "delete 1");
+        assert_eq!(
+            execute(&mut debugger, "continue"),
+            "halted: infinite loop at 1 (acc=10)\n"
+        );
+    }
+
+    #[test]
+    fn test_accumulator_breakpoint_before_instruction() {
+        let mut debugger = debugger();
+        execute(&mut debugger, "break acc == 1");
+
+        assert_eq!(
+            execute(&mut debugger, "step 5"),
+            "    0  nop +0   pc=1 acc=0\n    1  acc +1   pc=2 acc=1\nbreakpoint 1: acc == 1 (pc=2 acc=1)\n"
+        );
+    }
+
+    #[test]
+    fn test_watch() {
+        let mut debugger = debugger();
+        execute(&mut debugger, "watch");
+
+        assert_eq!(
+            execute(&mut debugger, "step 2"),
+            "    0  nop +0   pc=1 acc=0\n    1  acc +1   pc=2 acc=1\nwatch: acc 0 -> 1 at 1\n"
+        );
+    }
+
+    #[test]
+    fn test_halt_on_termination() {
+        let mut debugger = Debugger::new(parse_program("acc +2").unwrap());
+
+        assert_eq!(
+            execute(&mut debugger, "step 2"),
+            "    0  acc +2   pc=1 acc=2\nhalted: terminated (acc=2)\n"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use crate::vm::{Halt, Instruction, Interpreter, Operation};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Repair {
+    pub pointer: usize,
+    pub original: Instruction,
+    pub patched: Instruction,
+    pub accumulator: i32,
+}
+
+// Where the pointer goes after `instruction`, if it stays within the program
+// or lands right after it.
+fn successor(
+    instructions: &[Instruction],
+    pointer: usize,
+    instruction: &Instruction,
+) -> Option<usize> {
+    let target = match instruction.operation {
+        Operation::Jmp => pointer as i64 + instruction.argument as i64,
+        Operation::Acc | Operation::Nop => pointer as i64 + 1,
+    };
+
+    if 0 <= target && target as usize <= instructions.len() {
+        Some(target as usize)
+    } else {
+        None
+    }
+}
+
+// Every pointer that ends up after the last instruction, found by walking the
+// control flow graph backwards from there.
+fn terminating(instructions: &[Instruction]) -> Vec<bool> {
+    let end = instructions.len();
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); end + 1];
+
+    for (pointer, instruction) in instructions.iter().enumerate() {
+        if let Some(target) = successor(instructions, pointer, instruction) {
+            predecessors[target].push(pointer);
+        }
+    }
+
+    let mut terminating = vec![false; end + 1];
+    let mut pending = vec![end];
+    terminating[end] = true;
+
+    while let Some(pointer) = pending.pop() {
+        for &predecessor in predecessors[pointer].iter() {
+            if !terminating[predecessor] {
+                terminating[predecessor] = true;
+                pending.push(predecessor);
+            }
+        }
+    }
+
+    terminating
+}
+
+// Finds the one `jmp`/`nop` to flip so the program terminates, in linear time.
+// Only instructions on the looping path from 0 are candidates, and since none
+// of them can reach the end, a flipped one that lands on a terminating pointer
+// never comes back through itself.
+pub fn repair(instructions: &[Instruction]) -> Option<Repair> {
+    let terminating = terminating(instructions);
+
+    if terminating[0] {
+        return None;
+    }
+
+    let mut visited: HashSet<usize> = HashSet::new();
+    let mut pointer = 0;
+
+    while visited.insert(pointer) {
+        let original = *instructions.get(pointer)?;
+
+        if let Some(patched) = original.flipped() {
+            if let Some(true) =
+                successor(instructions, pointer, &patched).map(|target| terminating[target])
+            {
+                let mut interpreter = Interpreter::new(instructions.to_vec());
+                interpreter.instructions_mut()[pointer] = patched;
+
+                // The only other way to stop is an accumulator overflow
+                if interpreter.run() == Halt::Terminated {
+                    return Some(Repair {
+                        pointer,
+                        original,
+                        patched,
+                        accumulator: interpreter.accumulator(),
+                    });
+                }
+            }
+        }
+
+        pointer = successor(instructions, pointer, &original)?;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use helpers::Random;
+
+    use crate::vm::parse_program;
+
+    use super::*;
+
+    // The original approach: try every flip and rerun the whole program
+    fn brute_force(instructions: &[Instruction]) -> Vec<Repair> {
+        let mut repairs = Vec::new();
+
+        for (pointer, original) in instructions.iter().enumerate() {
+            let patched = match original.flipped() {
+                Some(patched) => patched,
+                None => continue,
+            };
+
+            let mut interpreter = Interpreter::new(instructions.to_vec());
+            interpreter.instructions_mut()[pointer] = patched;
+
+            if interpreter.run() == Halt::Terminated {
+                repairs.push(Repair {
+                    pointer,
+                    original: *original,
+                    patched,
+                    accumulator: interpreter.accumulator(),
+                });
+            }
+        }
+
+        repairs
+    }
+
+    fn programs(count: usize, length: usize) -> Vec<Vec<Instruction>> {
+        let mut random = Random::new(0x2545_f491_4f6c_dd1d);
+
+        (0..count)
+            .map(|_| {
+                (0..length)
+                    .map(|_| {
+                        let operation = match random.below(3) {
+                            0 => Operation::Acc,
+                            1 => Operation::Jmp,
+                            _ => Operation::Nop,
+                        };
+                        let argument = random.below(length as u64 * 2) as i32 - length as i32;
+
+                        Instruction {
+                            operation,
+                            argument,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_repair() {
+        let program = parse_program(
+            "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6",
+        )
+        .unwrap();
+
+        assert_eq!(
+            repair(&program),
+            Some(Repair {
+                pointer: 7,
+                original: "jmp -4".parse().unwrap(),
+                patched: "nop -4".parse().unwrap(),
+                accumulator: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn test_repair_terminating_program() {
+        assert_eq!(repair(&parse_program("acc +1\nnop +0").unwrap()), None);
+    }
+
+    #[test]
+    fn test_repair_matches_brute_force() {
+        let mut repaired = 0;
+
+        for program in programs(2000, 12) {
+            if Interpreter::new(program.clone()).run() == Halt::Terminated {
+                continue;
+            }
+
+            let repairs = brute_force(&program);
+
+            match repair(&program) {
+                Some(repair) => {
+                    assert!(repairs.contains(&repair));
+                    repaired += 1;
+                }
+                None => assert!(repairs.is_empty()),
+            }
+        }
+
+        assert!(0 < repaired);
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use helpers::{try_parse_input, ParseError};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Acc,
+    Jmp,
+    Nop,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Acc => write!(f, "acc"),
+            Operation::Jmp => write!(f, "jmp"),
+            Operation::Nop => write!(f, "nop"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstructionError {
+    UnknownOperation(String),
+    MissingArgument,
+    InvalidArgument(String),
+    TrailingInput(String),
+}
+
+impl Display for InstructionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InstructionError::UnknownOperation(operation) => write!(
+                f,
+                "unknown operation \"{}\" (expected acc, jmp or nop)",
+                operation
+            ),
+            InstructionError::MissingArgument => write!(f, "missing argument"),
+            InstructionError::InvalidArgument(argument) => {
+                write!(f, "invalid argument \"{}\" (expected +N or -N)", argument)
+            }
+            InstructionError::TrailingInput(input) => {
+                write!(f, "unexpected \"{}\" after the argument", input)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InstructionError {}
+
+impl FromStr for Operation {
+    type Err = InstructionError;
+
+    fn from_str(input: &str) -> Result<Operation, Self::Err> {
+        match input {
+            "acc" => Ok(Operation::Acc),
+            "jmp" => Ok(Operation::Jmp),
+            "nop" => Ok(Operation::Nop),
+            _ => Err(InstructionError::UnknownOperation(input.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub operation: Operation,
+    pub argument: i32,
+}
+
+impl Instruction {
+    // `jmp` and `nop` swapped, `acc` has no counterpart
+    pub fn flipped(&self) -> Option<Instruction> {
+        let operation = match self.operation {
+            Operation::Acc => return None,
+            Operation::Jmp => Operation::Nop,
+            Operation::Nop => Operation::Jmp,
+        };
+
+        Some(Instruction {
+            operation,
+            argument: self.argument,
+        })
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = InstructionError;
+
+    fn from_str(input: &str) -> Result<Instruction, Self::Err> {
+        let mut parts = input.split_whitespace();
+        let operation = parts.next().unwrap_or_default().parse()?;
+        let argument = parts.next().ok_or(InstructionError::MissingArgument)?;
+
+        if let Some(rest) = parts.next() {
+            return Err(InstructionError::TrailingInput(rest.to_string()));
+        }
+
+        // The puzzle always signs its arguments
+        let argument = match argument.as_bytes().first() {
+            Some(b'+') | Some(b'-') => argument.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| InstructionError::InvalidArgument(argument.to_string()))?;
+
+        Ok(Instruction {
+            operation,
+            argument,
+        })
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{} {:+}", self.operation, self.argument))
+    }
+}
+
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, ParseError<InstructionError>> {
+    try_parse_input(input)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Halt {
+    // The pointer reached the instruction right after the last one
+    Terminated,
+    InfiniteLoop { pointer: usize },
+    OutOfBounds { pointer: usize, target: i64 },
+    // The `acc` at `pointer` would take the accumulator past an `i32`
+    Overflow { pointer: usize },
+}
+
+impl Display for Halt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Halt::Terminated => write!(f, "terminated"),
+            Halt::InfiniteLoop { pointer } => write!(f, "infinite loop at {}", pointer),
+            Halt::OutOfBounds { pointer, target } => {
+                write!(f, "jump out of bounds from {} to {}", pointer, target)
+            }
+            Halt::Overflow { pointer } => write!(f, "accumulator overflow at {}", pointer),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct State {
+    pub pointer: usize,
+    pub accumulator: i32,
+}
+
+// One executed instruction and the state it left behind
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub pointer: usize,
+    pub instruction: Instruction,
+    pub state: State,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>5}  {:<8} pc={} acc={}",
+            self.pointer, self.instruction, self.state.pointer, self.state.accumulator
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Interpreter {
+    instructions: Vec<Instruction>,
+    state: State,
+    trace: Option<Vec<TraceEntry>>,
+}
+
+impl Interpreter {
+    pub fn new(instructions: Vec<Instruction>) -> Self {
+        Interpreter {
+            instructions,
+            state: State::default(),
+            trace: None,
+        }
+    }
+
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    pub fn instructions_mut(&mut self) -> &mut Vec<Instruction> {
+        &mut self.instructions
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn set_state(&mut self, state: State) {
+        self.state = state;
+    }
+
+    pub fn pointer(&self) -> usize {
+        self.state.pointer
+    }
+
+    pub fn accumulator(&self) -> i32 {
+        self.state.accumulator
+    }
+
+    // Empty unless tracing was enabled with `with_trace`
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn reset(&mut self) {
+        self.state = State::default();
+        self.trace.iter_mut().for_each(Vec::clear);
+    }
+
+    // Executes one instruction, or says why there is nothing to execute
+    pub fn step(&mut self) -> Result<TraceEntry, Halt> {
+        let pointer = self.state.pointer;
+        let instruction = match self.instructions.get(pointer) {
+            Some(instruction) => *instruction,
+            None => return Err(Halt::Terminated),
+        };
+
+        let target = match instruction.operation {
+            Operation::Jmp => pointer as i64 + instruction.argument as i64,
+            Operation::Acc | Operation::Nop => pointer as i64 + 1,
+        };
+
+        if target < 0 || self.instructions.len() < target as usize {
+            return Err(Halt::OutOfBounds { pointer, target });
+        }
+
+        if instruction.operation == Operation::Acc {
+            self.state.accumulator = self
+                .state
+                .accumulator
+                .checked_add(instruction.argument)
+                .ok_or(Halt::Overflow { pointer })?;
+        }
+        self.state.pointer = target as usize;
+
+        let entry = TraceEntry {
+            pointer,
+            instruction,
+            state: self.state,
+        };
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(entry);
+        }
+
+        Ok(entry)
+    }
+
+    // Runs until the program ends or is about to repeat an instruction
+    pub fn run(&mut self) -> Halt {
+        let mut visited: HashSet<usize> = HashSet::new();
+
+        loop {
+            if !visited.insert(self.state.pointer) {
+                return Halt::InfiniteLoop {
+                    pointer: self.state.pointer,
+                };
+            }
+
+            if let Err(halt) = self.step() {
+                return halt;
+            }
+        }
+    }
+}
+
+// Jump targets get labels in program order, `end` is the address right after
+// the last instruction.
+pub fn disassemble(instructions: &[Instruction]) -> String {
+    let target = |pointer: usize, instruction: &Instruction| match instruction.operation {
+        Operation::Jmp => Some(pointer as i64 + instruction.argument as i64),
+        Operation::Acc | Operation::Nop => None,
+    };
+    let in_program = |target: i64| 0 <= target && (target as usize) < instructions.len();
+
+    let mut labels: BTreeMap<usize, String> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(pointer, instruction)| target(pointer, instruction))
+        .filter(|&target| in_program(target))
+        .map(|target| (target as usize, String::new()))
+        .collect();
+
+    for (index, label) in labels.values_mut().enumerate() {
+        *label = format!("L{}", index + 1);
+    }
+
+    let mut output = String::new();
+
+    for (pointer, instruction) in instructions.iter().enumerate() {
+        let label = labels
+            .get(&pointer)
+            .map(|label| format!("{}:", label))
+            .unwrap_or_default();
+        let mut line = format!("{:<6}{:>4}  {}", label, pointer, instruction);
+
+        match target(pointer, instruction) {
+            Some(target) if in_program(target) => {
+                line = format!("{:<22}-> {}", line, labels[&(target as usize)])
+            }
+            Some(target) if target as usize == instructions.len() && 0 <= target => {
+                line = format!("{:<22}-> end", line)
+            }
+            Some(target) => line = format!("{:<22}-> out of bounds ({})", line, target),
+            None => {}
+        }
+
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "
+        nop +0
+        acc +1
+        jmp +4
+        acc +3
+        jmp -3
+        acc -99
+        acc +1
+        jmp -4
+        acc +6
+    ";
+
+    #[test]
+    fn test_parse_instruction() {
+        assert_eq!(
+            "jmp -3".parse(),
+            Ok(Instruction {
+                operation: Operation::Jmp,
+                argument: -3
+            })
+        );
+        assert_eq!(
+            "mul +3".parse::<Instruction>(),
+            Err(InstructionError::UnknownOperation("mul".to_string()))
+        );
+        assert_eq!(
+            "acc".parse::<Instruction>(),
+            Err(InstructionError::MissingArgument)
+        );
+        assert_eq!(
+            "acc 3".parse::<Instruction>(),
+            Err(InstructionError::InvalidArgument("3".to_string()))
+        );
+        assert_eq!(
+            "acc +3 +4".parse::<Instruction>(),
+            Err(InstructionError::TrailingInput("+4".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_program_reports_line() {
+        let error = parse_program("nop +0\nacc +1\njmp x\n").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "line 3: cannot parse \"jmp x\": invalid argument \"x\" (expected +N or -N)"
+        );
+    }
+
+    #[test]
+    fn test_halt_reasons() {
+        let mut interpreter = Interpreter::new(parse_program(PROGRAM).unwrap());
+        assert_eq!(interpreter.run(), Halt::InfiniteLoop { pointer: 1 });
+        assert_eq!(interpreter.accumulator(), 5);
+
+        let mut interpreter = Interpreter::new(parse_program("acc +2\nnop +0").unwrap());
+        assert_eq!(interpreter.run(), Halt::Terminated);
+        assert_eq!(interpreter.accumulator(), 2);
+
+        let mut interpreter = Interpreter::new(parse_program("nop +0\njmp -2").unwrap());
+        assert_eq!(
+            interpreter.run(),
+            Halt::OutOfBounds {
+                pointer: 1,
+                target: -1
+            }
+        );
+
+        let mut interpreter = Interpreter::new(
+            parse_program(
+                "acc +2147483647
+acc +1
+acc -5",
+            )
+            .unwrap(),
+        );
+        assert_eq!(interpreter.run(), Halt::Overflow { pointer: 1 });
+        assert_eq!(interpreter.accumulator(), i32::MAX);
+        assert_eq!(interpreter.pointer(), 1);
+        assert_eq!(
+            Halt::Overflow { pointer: 1 }.to_string(),
+            "accumulator overflow at 1"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut interpreter = Interpreter::new(parse_program(PROGRAM).unwrap()).with_trace();
+        interpreter.run();
+
+        let pointers: Vec<usize> = interpreter
+            .trace()
+            .iter()
+            .map(|entry| entry.pointer)
+            .collect();
+
+        assert_eq!(pointers, vec![0, 1, 2, 6, 7, 3, 4]);
+        assert_eq!(
+            interpreter.trace()[1].to_string(),
+            "    1  acc +1   pc=2 acc=1"
+        );
+
+        interpreter.reset();
+        assert!(interpreter.trace().is_empty());
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let program = parse_program(PROGRAM).unwrap();
+
+        assert_eq!(
+            disassemble(&program),
+            "         0  nop +0
+L1:      1  acc +1
+         2  jmp +4    -> L3
+L2:      3  acc +3
+         4  jmp -3    -> L1
+         5  acc -99
+L3:      6  acc +1
+         7  jmp -4    -> L2
+         8  acc +6
+"
+        );
+    }
+}
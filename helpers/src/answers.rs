@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::output::Status;
+
+// A small subset of TOML is enough for answer files:
+//
+//     part1 = "1675"
+//     part2 = 1532
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<u8, String>,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(io::Error),
+    Syntax { line: usize, text: String },
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::Io(error) => write!(f, "cannot read answers: {}", error),
+            AnswersError::Syntax { line, text } => write!(
+                f,
+                "line {}: expected `partN = \"answer\"`, found \"{}\"",
+                line, text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+impl From<io::Error> for AnswersError {
+    fn from(error: io::Error) -> Self {
+        AnswersError::Io(error)
+    }
+}
+
+impl Answers {
+    // A missing file means nothing has been recorded yet
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(input) => input.parse(),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        self.answers.get(&part).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, part: u8, answer: &str) {
+        self.answers.insert(part, answer.to_string());
+    }
+
+    pub fn check(&self, part: u8, answer: &str) -> Status {
+        match self.get(part) {
+            Some(expected) if expected == answer => Status::Pass,
+            Some(_) => Status::Fail,
+            None => Status::New,
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<(u8, String)> {
+        let (key, value) = line.split_once('=')?;
+        let part = key.trim().strip_prefix("part")?.parse().ok()?;
+        let value = value.trim();
+
+        let answer = if let Some(quoted) = value.strip_prefix('"') {
+            quoted
+                .strip_suffix('"')?
+                .replace("\\\"", "\"")
+                .replace("\\\\", "\\")
+        } else if !value.is_empty() && value.chars().all(|char| char.is_ascii_digit()) {
+            value.to_string()
+        } else {
+            return None;
+        };
+
+        Some((part, answer))
+    }
+}
+
+impl FromStr for Answers {
+    type Err = AnswersError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (part, answer) = Answers::parse_line(line).ok_or_else(|| AnswersError::Syntax {
+                line: index + 1,
+                text: line.to_string(),
+            })?;
+
+            answers.answers.insert(part, answer);
+        }
+
+        Ok(answers)
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (part, answer) in &self.answers {
+            writeln!(
+                f,
+                "part{} = \"{}\"",
+                part,
+                answer.replace('\\', "\\\\").replace('"', "\\\"")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers: Answers = "
+            # day 8
+            part1 = \"1675\"
+            part2 = 1532
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(answers.get(1), Some("1675"));
+        assert_eq!(answers.get(2), Some("1532"));
+        assert_eq!(answers.get(3), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = "part1 = \"1\"\npart2 = nope\n"
+            .parse::<Answers>()
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected `partN = \"answer\"`, found \"part2 = nope\""
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = "part1 = \"1675\"".parse().unwrap();
+
+        assert_eq!(answers.check(1, "1675"), Status::Pass);
+        assert_eq!(answers.check(1, "1676"), Status::Fail);
+        assert_eq!(answers.check(2, "1532"), Status::New);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let mut answers = Answers::default();
+        answers.insert(2, "a,\"b\"");
+        answers.insert(1, "42");
+
+        let output = answers.to_string();
+
+        assert_eq!(output, "part1 = \"42\"\npart2 = \"a,\\\"b\\\"\"\n");
+        assert_eq!(output.parse::<Answers>().unwrap(), answers);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let answers = Answers::load("/nonexistent/answers.toml").unwrap();
+
+        assert_eq!(answers, Answers::default());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = env::temp_dir().join("helpers_answers_test_save_and_load.toml");
+        let mut answers = Answers::default();
+        answers.insert(1, "1675");
+
+        answers.save(&path).unwrap();
+        let result = Answers::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap(), answers);
+    }
+}
@@ -0,0 +1,568 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use crate::grid::{Grid, Point};
+
+// The space cells live in. Bounded topologies list every cell so the
+// automaton can keep one flag per cell, unbounded ones grow around the live
+// cells.
+pub trait Topology {
+    type Cell: Copy + Eq + Hash;
+
+    fn cells(&self) -> Option<Vec<Self::Cell>>;
+
+    // The neighbours of each cell as positions in `cells`
+    fn neighbour_indices(&self) -> Option<Vec<Vec<usize>>> {
+        let cells = self.cells()?;
+        let index: HashMap<Self::Cell, usize> = cells
+            .iter()
+            .enumerate()
+            .map(|(position, &cell)| (cell, position))
+            .collect();
+
+        Some(
+            cells
+                .iter()
+                .map(|&cell| {
+                    let mut neighbours = Vec::new();
+                    self.for_each_neighbour(cell, |neighbour| {
+                        neighbours.extend(index.get(&neighbour));
+                    });
+                    neighbours
+                })
+                .collect(),
+        )
+    }
+
+    fn for_each_neighbour<F: FnMut(Self::Cell)>(&self, cell: Self::Cell, f: F);
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Neighbourhood {
+    VonNeumann,
+    Moore,
+    // The first cell in each of the eight directions, skipping non-cells
+    LineOfSight,
+}
+
+#[derive(Debug, Clone)]
+pub struct SquareGrid {
+    cells: Vec<Point>,
+    index: HashMap<Point, usize>,
+    neighbours: Vec<Vec<usize>>,
+}
+
+impl SquareGrid {
+    pub fn new<T, F>(grid: &Grid<T>, neighbourhood: Neighbourhood, is_cell: F) -> Self
+    where
+        F: Fn(&T) -> bool,
+    {
+        let cell = |point: &Point| is_cell(&grid[*point]);
+        let cells: Vec<Point> = grid.points().filter(cell).collect();
+        let index: HashMap<Point, usize> = cells
+            .iter()
+            .enumerate()
+            .map(|(position, &point)| (point, position))
+            .collect();
+        let neighbours = cells
+            .iter()
+            .map(|&point| {
+                let neighbours: Vec<Point> = match neighbourhood {
+                    Neighbourhood::VonNeumann => grid.neighbours4(point).filter(cell).collect(),
+                    Neighbourhood::Moore => grid.neighbours8(point).filter(cell).collect(),
+                    Neighbourhood::LineOfSight => Grid::<T>::directions8()
+                        .filter_map(|direction| {
+                            grid.ray(point, direction)
+                                .map(|(point, _)| point)
+                                .find(cell)
+                        })
+                        .collect(),
+                };
+
+                neighbours
+                    .iter()
+                    .map(|neighbour| index[neighbour])
+                    .collect()
+            })
+            .collect();
+
+        SquareGrid {
+            cells,
+            index,
+            neighbours,
+        }
+    }
+}
+
+impl Topology for SquareGrid {
+    type Cell = Point;
+
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        Some(self.cells.clone())
+    }
+
+    fn neighbour_indices(&self) -> Option<Vec<Vec<usize>>> {
+        Some(self.neighbours.clone())
+    }
+
+    fn for_each_neighbour<F: FnMut(Self::Cell)>(&self, cell: Self::Cell, mut f: F) {
+        if let Some(&position) = self.index.get(&cell) {
+            for &neighbour in self.neighbours[position].iter() {
+                f(self.cells[neighbour]);
+            }
+        }
+    }
+}
+
+// Unbounded N-dimensional lattice with the Moore neighbourhood (3^N - 1 cells)
+#[derive(Debug, Clone)]
+pub struct Lattice<const N: usize> {
+    offsets: Vec<[i32; N]>,
+}
+
+impl<const N: usize> Lattice<N> {
+    pub fn new() -> Self {
+        let mut offsets = vec![[0; N]];
+
+        for dimension in 0..N {
+            offsets = offsets
+                .into_iter()
+                .flat_map(|offset| {
+                    (-1..=1).map(move |delta| {
+                        let mut offset = offset;
+                        offset[dimension] = delta;
+                        offset
+                    })
+                })
+                .collect();
+        }
+
+        offsets.retain(|offset| offset.iter().any(|&delta| delta != 0));
+
+        Lattice { offsets }
+    }
+}
+
+impl<const N: usize> Default for Lattice<N> {
+    fn default() -> Self {
+        Lattice::new()
+    }
+}
+
+impl<const N: usize> Topology for Lattice<N> {
+    type Cell = [i32; N];
+
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        None
+    }
+
+    fn for_each_neighbour<F: FnMut(Self::Cell)>(&self, cell: Self::Cell, mut f: F) {
+        for offset in self.offsets.iter() {
+            let mut neighbour = cell;
+
+            for (coordinate, delta) in neighbour.iter_mut().zip(offset.iter()) {
+                *coordinate += delta;
+            }
+
+            f(neighbour);
+        }
+    }
+}
+
+// Unbounded hexagonal grid in axial coordinates `(q, r)`, where `q` grows to
+// the east and `r` to the south east.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct HexGrid;
+
+impl HexGrid {
+    pub const DIRECTIONS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+}
+
+impl Topology for HexGrid {
+    type Cell = (i32, i32);
+
+    fn cells(&self) -> Option<Vec<Self::Cell>> {
+        None
+    }
+
+    fn for_each_neighbour<F: FnMut(Self::Cell)>(&self, (q, r): Self::Cell, mut f: F) {
+        for (dq, dr) in HexGrid::DIRECTIONS.iter() {
+            f((q + dq, r + dr));
+        }
+    }
+}
+
+// Birth/survival rule indexed by the number of live neighbours
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    birth: Vec<bool>,
+    survival: Vec<bool>,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let table = |counts: &[usize]| {
+            let mut table = vec![false; counts.iter().max().map_or(0, |max| max + 1)];
+
+            for &count in counts {
+                table[count] = true;
+            }
+
+            table
+        };
+
+        Rule {
+            birth: table(birth),
+            survival: table(survival),
+        }
+    }
+
+    pub fn born(&self, neighbours: usize) -> bool {
+        self.birth.get(neighbours).copied().unwrap_or(false)
+    }
+
+    pub fn survives(&self, neighbours: usize) -> bool {
+        self.survival.get(neighbours).copied().unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleError(String);
+
+impl Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule \"{}\" (expected \"B3/S23\")", self.0)
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+// "B3/S23" notation, one digit per neighbour count
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || RuleError(input.to_string());
+        let counts = |part: &str, prefix: char| {
+            part.trim()
+                .strip_prefix(prefix)
+                .ok_or_else(error)?
+                .chars()
+                .map(|char| {
+                    char.to_digit(10)
+                        .map(|digit| digit as usize)
+                        .ok_or_else(error)
+                })
+                .collect::<Result<Vec<usize>, RuleError>>()
+        };
+
+        let (birth, survival) = input.split_once('/').ok_or_else(error)?;
+
+        Ok(Rule::new(&counts(birth, 'B')?, &counts(survival, 'S')?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutomatonError {
+    UnboundedBirth,
+}
+
+impl Display for AutomatonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutomatonError::UnboundedBirth => write!(
+                f,
+                "rules with birth on zero neighbours need a bounded topology"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AutomatonError {}
+
+#[derive(Debug, Clone)]
+enum State<C> {
+    // Bounded topologies, one flag per cell in the order of `Topology::cells`
+    Dense {
+        cells: Vec<C>,
+        index: HashMap<C, usize>,
+        neighbours: Vec<Vec<usize>>,
+        live: Vec<bool>,
+        next: Vec<bool>,
+    },
+    // Unbounded topologies, only the live cells
+    Sparse(HashSet<C>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton<T: Topology> {
+    topology: T,
+    rule: Rule,
+    state: State<T::Cell>,
+    generation: usize,
+}
+
+impl<T: Topology> Automaton<T> {
+    pub fn new<I>(topology: T, rule: Rule, live: I) -> Result<Self, AutomatonError>
+    where
+        I: IntoIterator<Item = T::Cell>,
+    {
+        let state = match (topology.cells(), topology.neighbour_indices()) {
+            (Some(cells), Some(neighbours)) => {
+                let index: HashMap<T::Cell, usize> = cells
+                    .iter()
+                    .enumerate()
+                    .map(|(position, &cell)| (cell, position))
+                    .collect();
+                let mut flags = vec![false; cells.len()];
+
+                for cell in live {
+                    if let Some(&position) = index.get(&cell) {
+                        flags[position] = true;
+                    }
+                }
+
+                State::Dense {
+                    next: flags.clone(),
+                    live: flags,
+                    cells,
+                    index,
+                    neighbours,
+                }
+            }
+            _ if rule.born(0) => return Err(AutomatonError::UnboundedBirth),
+            _ => State::Sparse(live.into_iter().collect()),
+        };
+
+        Ok(Automaton {
+            topology,
+            rule,
+            state,
+            generation: 0,
+        })
+    }
+
+    pub fn live(&self) -> Vec<T::Cell> {
+        match &self.state {
+            State::Dense { cells, live, .. } => cells
+                .iter()
+                .zip(live.iter())
+                .filter(|(_, &live)| live)
+                .map(|(&cell, _)| cell)
+                .collect(),
+            State::Sparse(live) => live.iter().copied().collect(),
+        }
+    }
+
+    pub fn is_live(&self, cell: T::Cell) -> bool {
+        match &self.state {
+            State::Dense { index, live, .. } => {
+                index.get(&cell).is_some_and(|&position| live[position])
+            }
+            State::Sparse(live) => live.contains(&cell),
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        match &self.state {
+            State::Dense { live, .. } => live.iter().filter(|&&live| live).count(),
+            State::Sparse(live) => live.len(),
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Returns whether any cell changed
+    pub fn step(&mut self) -> bool {
+        let rule = &self.rule;
+        let changed = match &mut self.state {
+            State::Dense {
+                neighbours,
+                live,
+                next,
+                ..
+            } => {
+                for (position, neighbours) in neighbours.iter().enumerate() {
+                    let count = neighbours
+                        .iter()
+                        .filter(|&&neighbour| live[neighbour])
+                        .count();
+
+                    next[position] = match live[position] {
+                        true => rule.survives(count),
+                        false => rule.born(count),
+                    };
+                }
+
+                let changed = live != next;
+                std::mem::swap(live, next);
+
+                changed
+            }
+            State::Sparse(live) => {
+                let mut counts: HashMap<T::Cell, usize> = HashMap::new();
+
+                for &cell in live.iter() {
+                    counts.entry(cell).or_insert(0);
+                    self.topology.for_each_neighbour(cell, |neighbour| {
+                        *counts.entry(neighbour).or_insert(0) += 1
+                    });
+                }
+
+                let next: HashSet<T::Cell> = counts
+                    .into_iter()
+                    .filter(|(cell, count)| match live.contains(cell) {
+                        true => rule.survives(*count),
+                        false => rule.born(*count),
+                    })
+                    .map(|(cell, _)| cell)
+                    .collect();
+
+                let changed = next != *live;
+                *live = next;
+
+                changed
+            }
+        };
+
+        self.generation += 1;
+
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) -> &mut Self {
+        for _ in 0..generations {
+            self.step();
+        }
+
+        self
+    }
+
+    // Returns the number of generations it took to stop changing, or `None`
+    // when it still changes after `limit` steps, like an oscillator does
+    pub fn run_until_stable(&mut self, limit: usize) -> Option<usize> {
+        (0..limit).find(|_| !self.step())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule() {
+        let rule: Rule = "B3/S23".parse().unwrap();
+
+        assert_eq!(rule, Rule::new(&[3], &[2, 3]));
+        assert!(rule.born(3));
+        assert!(!rule.born(2));
+        assert!(rule.survives(2));
+        assert!(!rule.survives(4));
+        assert!("3/23".parse::<Rule>().is_err());
+        assert!("Bx/S23".parse::<Rule>().is_err());
+    }
+
+    #[test]
+    fn test_lattice_neighbours() {
+        let mut neighbours = Vec::new();
+        Lattice::<2>::new().for_each_neighbour([0, 0], |cell| neighbours.push(cell));
+
+        assert_eq!(Lattice::<3>::new().offsets.len(), 26);
+        assert_eq!(Lattice::<4>::new().offsets.len(), 80);
+        assert_eq!(neighbours.len(), 8);
+        assert!(!neighbours.contains(&[0, 0]));
+        assert!(neighbours.contains(&[-1, 1]));
+    }
+
+    #[test]
+    fn test_blinker() {
+        let mut automaton = Automaton::new(
+            Lattice::<2>::new(),
+            "B3/S23".parse().unwrap(),
+            vec![[0, -1], [0, 0], [0, 1]],
+        )
+        .unwrap();
+
+        automaton.step();
+        let mut live = automaton.live();
+        live.sort();
+
+        assert_eq!(live, vec![[-1, 0], [0, 0], [1, 0]]);
+
+        automaton.step();
+        assert!(automaton.is_live([0, -1]));
+        assert_eq!(automaton.generation(), 2);
+    }
+
+    #[test]
+    fn test_run_until_stable() {
+        // A block is a still life
+        let mut automaton = Automaton::new(
+            Lattice::<2>::new(),
+            Rule::new(&[3], &[2, 3]),
+            vec![[0, 0], [0, 1], [1, 0], [1, 1], [5, 5]],
+        )
+        .unwrap();
+
+        assert_eq!(automaton.run_until_stable(10), Some(1));
+        assert_eq!(automaton.population(), 4);
+    }
+
+    #[test]
+    fn test_oscillator_never_stable() {
+        let mut automaton = Automaton::new(
+            Lattice::<2>::new(),
+            Rule::new(&[3], &[2, 3]),
+            vec![[0, -1], [0, 0], [0, 1]],
+        )
+        .unwrap();
+
+        assert_eq!(automaton.run_until_stable(100), None);
+        assert_eq!(automaton.generation(), 100);
+        assert!(automaton.is_live([0, -1]));
+    }
+
+    #[test]
+    fn test_square_grid_line_of_sight() {
+        let grid = Grid::parse(
+            "
+            L.L
+            ...
+            L.L
+            ",
+            Some,
+        )
+        .unwrap();
+        let topology = SquareGrid::new(&grid, Neighbourhood::LineOfSight, |&cell| cell == 'L');
+        let mut neighbours = Vec::new();
+        topology.for_each_neighbour((0, 0), |cell| neighbours.push(cell));
+
+        assert_eq!(topology.cells().unwrap().len(), 4);
+        assert_eq!(neighbours, vec![(2, 0), (0, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_bounded_birth_on_zero() {
+        let grid = Grid::parse("LL\nLL", Some).unwrap();
+        let topology = SquareGrid::new(&grid, Neighbourhood::Moore, |&cell| cell == 'L');
+        let mut automaton =
+            Automaton::new(topology, Rule::new(&[0], &[0, 1, 2, 3]), vec![]).unwrap();
+
+        automaton.step();
+
+        assert_eq!(automaton.population(), 4);
+        assert!(automaton.is_live((1, 1)));
+    }
+
+    #[test]
+    fn test_unbounded_birth_on_zero() {
+        assert_eq!(
+            Automaton::new(HexGrid, Rule::new(&[0], &[]), vec![]).err(),
+            Some(AutomatonError::UnboundedBirth)
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt::{self, Display};
+use std::io::{stdout, Write};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::instant::BasicInstant;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BenchLimit {
+    Runs(usize),
+    Budget(Duration),
+}
+
+impl Default for BenchLimit {
+    fn default() -> Self {
+        BenchLimit::Budget(Duration::from_secs(1))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchLimitError(String);
+
+impl Display for BenchLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid bench limit \"{}\" (expected a run count like \"100\" or a budget like \"500ms\" or \"2s\")",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for BenchLimitError {}
+
+impl FromStr for BenchLimit {
+    type Err = BenchLimitError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || BenchLimitError(input.to_string());
+        let number = |value: &str| value.parse::<u64>().map_err(|_| error());
+        let input = input.trim();
+
+        let limit = if let Some(millis) = input.strip_suffix("ms") {
+            BenchLimit::Budget(Duration::from_millis(number(millis)?))
+        } else if let Some(secs) = input.strip_suffix('s') {
+            BenchLimit::Budget(Duration::from_secs(number(secs)?))
+        } else {
+            BenchLimit::Runs(number(input)? as usize)
+        };
+
+        match limit {
+            BenchLimit::Runs(0) => Err(error()),
+            BenchLimit::Budget(budget) if budget == Duration::default() => Err(error()),
+            limit => Ok(limit),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut samples = samples.to_vec();
+        samples.sort();
+
+        let runs = samples.len();
+        let middle = runs / 2;
+        let median = if runs.is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+        // Nearest-rank percentile
+        let p95 = samples[(runs * 95).div_ceil(100) - 1];
+        let total: Duration = samples.iter().sum();
+
+        Some(Stats {
+            runs,
+            min: samples[0],
+            median,
+            mean: total / runs as u32,
+            p95,
+        })
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, median {:.2?}, mean {:.2?}, p95 {:.2?}, {} runs",
+            self.min, self.median, self.mean, self.p95, self.runs
+        )
+    }
+}
+
+pub fn bench<S, R>(name: &str, limit: BenchLimit, solver: S)
+where
+    R: Display,
+    S: Fn() -> R,
+{
+    raw_bench::<_, Instant, _, _>(stdout(), limit, name, solver);
+}
+
+fn sample<N, S, R>(limit: BenchLimit, solver: S) -> (R, Vec<Duration>)
+where
+    N: BasicInstant,
+    S: Fn() -> R,
+{
+    let mut samples = Vec::new();
+    let mut total = Duration::default();
+
+    loop {
+        let now = N::now();
+        let solution = solver();
+        let elapsed = now.elapsed();
+
+        samples.push(elapsed);
+        total += elapsed;
+
+        let done = match limit {
+            BenchLimit::Runs(runs) => runs <= samples.len(),
+            BenchLimit::Budget(budget) => budget <= total,
+        };
+
+        if done {
+            return (solution, samples);
+        }
+    }
+}
+
+fn raw_bench<W, N, S, R>(mut writer: W, limit: BenchLimit, name: &str, solver: S)
+where
+    W: Write,
+    N: BasicInstant,
+    S: Fn() -> R,
+    R: Display,
+{
+    let (solution, samples) = sample::<N, _, _>(limit, solver);
+    let stats = Stats::from_samples(&samples).unwrap();
+
+    writeln!(&mut writer, "{}: {} ({})", name, solution, stats)
+        .expect("Something went wrong writing the solution!")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instant;
+
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values
+            .iter()
+            .map(|&value| Duration::from_millis(value))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!("100".parse(), Ok(BenchLimit::Runs(100)));
+        assert_eq!(
+            "500ms".parse(),
+            Ok(BenchLimit::Budget(Duration::from_millis(500)))
+        );
+        assert_eq!("2s".parse(), Ok(BenchLimit::Budget(Duration::from_secs(2))));
+        assert!("0".parse::<BenchLimit>().is_err());
+        assert!("fast".parse::<BenchLimit>().is_err());
+    }
+
+    #[test]
+    fn test_stats_odd_samples() {
+        let stats = Stats::from_samples(&millis(&[5, 1, 3])).unwrap();
+
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_stats_even_samples() {
+        let samples: Vec<u64> = (1..=20).rev().collect();
+        let stats = Stats::from_samples(&millis(&samples)).unwrap();
+
+        assert_eq!(stats.runs, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(10_500));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+    }
+
+    #[test]
+    fn test_stats_without_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_sample_runs() {
+        instant::fake::with_fake_elapsed(Duration::from_millis(10));
+
+        let (solution, samples) =
+            sample::<instant::fake::Instant, _, _>(BenchLimit::Runs(7), || 42);
+
+        assert_eq!(solution, 42);
+        assert_eq!(samples.len(), 7);
+    }
+
+    #[test]
+    fn test_sample_budget() {
+        instant::fake::with_fake_elapsed(Duration::from_millis(10));
+
+        let (_, samples) = sample::<instant::fake::Instant, _, _>(
+            BenchLimit::Budget(Duration::from_millis(35)),
+            || 42,
+        );
+
+        assert_eq!(samples.len(), 4);
+    }
+
+    #[test]
+    fn test_bench() {
+        instant::fake::with_fake_elapsed(Duration::from_millis(10));
+        let mut output = Vec::new();
+
+        raw_bench::<_, instant::fake::Instant, _, _>(
+            &mut output,
+            BenchLimit::Runs(3),
+            "test",
+            || 3066,
+        );
+        let output = String::from_utf8(output).expect("Not UTF-8");
+
+        assert_eq!(
+            output,
+            "test: 3066 (min 10.00ms, median 10.00ms, mean 10.00ms, p95 10.00ms, 3 runs)\n"
+        );
+    }
+}
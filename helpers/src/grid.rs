@@ -0,0 +1,495 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::input::input_lines;
+
+pub type Point = (usize, usize);
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+// Cells are stored row by row, `(x, y)` is `(column, row)` and `(0, 0)` is the
+// top left corner as it appears in the puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidCell {
+        line: usize,
+        column: usize,
+        char: char,
+    },
+}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid is empty"),
+            GridError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} cells, found {}",
+                line, expected, found
+            ),
+            GridError::InvalidCell { line, column, char } => {
+                write!(
+                    f,
+                    "line {}, column {}: invalid cell '{}'",
+                    line, column, char
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let width = rows.first().map(Vec::len).ok_or(GridError::Empty)?;
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+
+        for (index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(GridError::Ragged {
+                    line: index + 1,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+
+            cells.extend(row);
+        }
+
+        if width == 0 {
+            return Err(GridError::Empty);
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn from_lines<F>(lines: &[&str], mut cell: F) -> Result<Self, GridError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = lines
+            .iter()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, char)| {
+                        cell(char).ok_or(GridError::InvalidCell {
+                            line: y + 1,
+                            column: x + 1,
+                            char,
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<Vec<T>>, GridError>>()?;
+
+        Grid::from_rows(rows)
+    }
+
+    // Blank lines and surrounding whitespace are ignored, like `input_lines`
+    pub fn parse<F>(input: &str, cell: F) -> Result<Self, GridError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let lines: Vec<&str> = input_lines(input);
+
+        Grid::from_lines(&lines, cell)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.cells[point.1 * self.width + point.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.cells[point.1 * self.width + point.0])
+        } else {
+            None
+        }
+    }
+
+    pub fn offset(&self, (x, y): Point, (dx, dy): (isize, isize)) -> Option<Point> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+
+        if self.contains((x, y)) {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    // The grid repeats itself in every direction
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+
+        &self[(x, y)]
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+
+        (0..self.width * self.height).map(move |index| (index % width, index / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS_4
+            .iter()
+            .filter_map(move |&direction| self.offset(point, direction))
+    }
+
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .filter_map(move |&direction| self.offset(point, direction))
+    }
+
+    pub fn directions8() -> impl Iterator<Item = (isize, isize)> {
+        NEIGHBOURS_8.iter().copied()
+    }
+
+    // Every point from `start` (excluded) in `direction` until the edge
+    pub fn ray(
+        &self,
+        start: Point,
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = (Point, &T)> {
+        let mut point = start;
+
+        std::iter::from_fn(move || {
+            point = self.offset(point, direction)?;
+
+            Some((point, &self[point]))
+        })
+    }
+
+    pub fn map<U, F>(&self, mut f: F) -> Grid<U>
+    where
+        F: FnMut(Point, &T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(point, cell)| f(point, cell)).collect(),
+        }
+    }
+
+    pub fn count<F>(&self, mut predicate: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.cells.iter().filter(|cell| predicate(cell)).count()
+    }
+
+    fn remap<F>(&self, width: usize, height: usize, source: F) -> Self
+    where
+        T: Clone,
+        F: Fn(usize, usize) -> Point,
+    {
+        let cells = (0..width * height)
+            .map(|index| self[source(index % width, index / width)].clone())
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // Clockwise
+    pub fn rotate(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    // Mirror left to right
+    pub fn flip_x(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+
+        self.remap(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    // Mirror top to bottom
+    pub fn flip_y(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+
+        self.remap(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    // The four rotations followed by the four rotations of the mirrored grid
+    pub fn orientations(&self) -> Vec<Self>
+    where
+        T: Clone,
+    {
+        let mut orientations = Vec::with_capacity(8);
+        let mut grid = self.clone();
+
+        for index in 0..8 {
+            if index == 4 {
+                grid = self.flip_x();
+            }
+
+            let next = grid.rotate();
+            orientations.push(grid);
+            grid = next;
+        }
+
+        orientations
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    // Like `from_rows`, a grid needs at least one row and one column
+    pub fn filled(width: usize, height: usize, value: T) -> Result<Self, GridError> {
+        if width == 0 || height == 0 {
+            return Err(GridError::Empty);
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point).expect("Point out of the grid")
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point).expect("Point out of the grid")
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse(
+            "
+            abc
+            def
+            ",
+            Some,
+        )
+        .unwrap()
+    }
+
+    fn to_string(grid: &Grid<char>) -> String {
+        grid.to_string().replace('\n', "|")
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Grid::parse("", Some), Err(GridError::Empty));
+        assert_eq!(
+            Grid::parse("ab\nabc", Some),
+            Err(GridError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Grid::parse("..\n.x", |char| if char == '.' { Some(()) } else { None })
+                .unwrap_err()
+                .to_string(),
+            "line 2, column 2: invalid cell 'x'"
+        );
+    }
+
+    #[test]
+    fn test_filled() {
+        let grid = Grid::filled(2, 3, '.').unwrap();
+
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(grid.to_string(), "..\n..\n..\n");
+        assert_eq!(Grid::filled(0, 3, '.'), Err(GridError::Empty));
+        assert_eq!(Grid::filled(2, 0, '.'), Err(GridError::Empty));
+    }
+
+    #[test]
+    fn test_get_wrapping() {
+        let grid = grid();
+
+        assert_eq!(*grid.get_wrapping(3, 0), 'a');
+        assert_eq!(*grid.get_wrapping(-1, -1), 'f');
+        assert_eq!(*grid.get_wrapping(7, 3), 'e');
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours8((1, 0)).collect::<Vec<_>>(),
+            vec![(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.ray((0, 0), (1, 0)).collect::<Vec<_>>(),
+            vec![((1, 0), &'b'), ((2, 0), &'c')]
+        );
+        assert_eq!(
+            grid.ray((0, 0), (1, 1)).collect::<Vec<_>>(),
+            vec![((1, 1), &'e')]
+        );
+        assert_eq!(grid.ray((0, 0), (-1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_map_and_count() {
+        let grid = grid().map(|(x, y), _| x + y);
+
+        assert_eq!(grid[(2, 1)], 3);
+        assert_eq!(grid.count(|&value| value > 1), 3);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = grid();
+
+        assert_eq!(to_string(&grid.rotate()), "da|eb|fc|");
+        assert_eq!(to_string(&grid.flip_x()), "cba|fed|");
+        assert_eq!(to_string(&grid.flip_y()), "def|abc|");
+        assert_eq!(to_string(&grid.transpose()), "ad|be|cf|");
+        assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+    }
+
+    #[test]
+    fn test_orientations() {
+        let orientations = grid().orientations();
+
+        assert_eq!(orientations.len(), 8);
+        assert_eq!(orientations[0], grid());
+        assert_eq!(orientations[4], grid().flip_x());
+
+        for (index, orientation) in orientations.iter().enumerate() {
+            assert!(orientations[index + 1..]
+                .iter()
+                .all(|other| other != orientation));
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use std::fmt::Debug;
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
 use std::iter::FromIterator;
 use std::str::FromStr;
 
@@ -9,7 +10,7 @@ where
     input
         .lines()
         .map(|line| line.trim())
-        .filter(|line| false == line.is_empty())
+        .filter(|line| !line.is_empty())
         .collect()
 }
 
@@ -20,21 +21,96 @@ where
     input
         .split(pattern)
         .map(|line| line.trim())
-        .filter(|line| false == line.is_empty())
+        .filter(|line| !line.is_empty())
         .collect()
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Position {
+    Line(usize),
+    Chunk(usize),
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Position::Line(number) => write!(f, "line {}", number),
+            Position::Chunk(number) => write!(f, "chunk {}", number),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError<E> {
+    pub position: Position,
+    pub text: String,
+    pub error: E,
+}
+
+impl<E: Display> Display for ParseError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: cannot parse \"{}\": {}",
+            self.position, self.text, self.error
+        )
+    }
+}
+
+impl<E: Error + 'static> Error for ParseError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+// Numbers are 1-based and count the blank lines or chunks that get skipped,
+// so they point at the same place an editor would.
+fn try_parse<'a, T, R, I>(
+    items: I,
+    position: fn(usize) -> Position,
+) -> Result<R, ParseError<T::Err>>
+where
+    T: FromStr,
+    R: FromIterator<T>,
+    I: Iterator<Item = &'a str>,
+{
+    items
+        .enumerate()
+        .map(|(index, item)| (position(index + 1), item.trim()))
+        .filter(|(_, item)| !item.is_empty())
+        .map(|(position, item)| {
+            item.parse().map_err(|error| ParseError {
+                position,
+                text: item.to_string(),
+                error,
+            })
+        })
+        .collect()
+}
+
+pub fn try_parse_split_input<T: FromStr, R>(
+    input: &str,
+    pattern: &str,
+) -> Result<R, ParseError<T::Err>>
+where
+    R: FromIterator<T>,
+{
+    try_parse(input.split(pattern), Position::Chunk)
+}
+
+pub fn try_parse_input<T: FromStr, R>(input: &str) -> Result<R, ParseError<T::Err>>
+where
+    R: FromIterator<T>,
+{
+    try_parse(input.lines(), Position::Line)
+}
+
 pub fn parse_split_input<T: FromStr, R>(input: &str, pattern: &str) -> R
 where
     T::Err: Debug,
     R: FromIterator<T>,
 {
-    input
-        .split(pattern)
-        .map(|line| line.trim())
-        .filter(|line| false == line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect()
+    try_parse_split_input(input, pattern).expect("Something went wrong parsing the input!")
 }
 
 pub fn parse_input<T: FromStr, R>(input: &str) -> R
@@ -42,17 +118,13 @@ where
     T::Err: Debug,
     R: FromIterator<T>,
 {
-    input
-        .lines()
-        .map(|line| line.trim())
-        .filter(|line| false == line.is_empty())
-        .map(|line| line.parse().unwrap())
-        .collect()
+    try_parse_input(input).expect("Something went wrong parsing the input!")
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
+    use std::num::ParseIntError;
 
     use super::*;
 
@@ -159,4 +231,57 @@ mod tests {
         let expected: HashSet<u64> = [1721, 979, 366, 299, 675, 1456].iter().cloned().collect();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_parse_split_input_to_vec() {
+        let result: Vec<usize> = parse_split_input("0,3,6", ",");
+
+        assert_eq!(result, vec![0, 3, 6]);
+    }
+
+    #[test]
+    fn test_try_parse_input() {
+        let result: Result<Vec<u64>, ParseError<ParseIntError>> = try_parse_input("1721\n979\n");
+
+        assert_eq!(result, Ok(vec![1721, 979]));
+    }
+
+    #[test]
+    fn test_try_parse_input_reports_line() {
+        let input = "
+            1721
+            979
+
+            abc
+            366
+        ";
+
+        let error = try_parse_input::<u64, Vec<u64>>(input).unwrap_err();
+
+        assert_eq!(error.position, Position::Line(5));
+        assert_eq!(error.text, "abc");
+        assert_eq!(error.error, "abc".parse::<u64>().unwrap_err());
+        assert_eq!(
+            error.to_string(),
+            "line 5: cannot parse \"abc\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_try_parse_split_input_reports_chunk() {
+        let error = try_parse_split_input::<usize, Vec<usize>>("0,3,,x,6", ",").unwrap_err();
+
+        assert_eq!(error.position, Position::Chunk(4));
+        assert_eq!(error.text, "x");
+        assert_eq!(
+            error.to_string(),
+            "chunk 4: cannot parse \"x\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Something went wrong parsing the input!")]
+    fn test_parse_input_panics_on_error() {
+        let _: Vec<u64> = parse_input("1\nx\n");
+    }
 }
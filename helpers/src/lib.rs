@@ -1,10 +1,36 @@
+pub use answers::{Answers, AnswersError};
+pub use automaton::{
+    Automaton, AutomatonError, HexGrid, Lattice, Neighbourhood, Rule, RuleError, SquareGrid,
+    Topology,
+};
+pub use bench::{bench, BenchLimit, BenchLimitError, Stats};
+pub use grid::{Grid, GridError, Point};
 pub use input::input_lines;
 pub use input::parse_input;
 pub use input::parse_split_input;
 pub use input::split_input;
-pub use run::run;
+pub use input::try_parse_input;
+pub use input::try_parse_split_input;
+pub use input::{ParseError, Position};
+pub use output::{
+    Csv, Formatter, Human, JsonLines, OutputFormat, OutputFormatError, ParseTime, Record, Status,
+};
+pub use random::Random;
+pub use registry::{DaySelection, ParsedSolver, Parser, Registry, SelectionError, Solver};
+pub use run::{parse_timed, run, run_formatted, run_parsed, run_with_parse, Parsed};
+pub use sections::{Section, SectionError, SectionKey, Sections};
+pub use source::{load_input, InputSource, INPUT_ENV};
 
+mod answers;
+mod automaton;
+mod bench;
 mod debug;
+mod grid;
 mod input;
 mod instant;
+mod output;
+mod random;
+mod registry;
 mod run;
+mod sections;
+mod source;
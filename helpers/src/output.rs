@@ -0,0 +1,313 @@
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Pass,
+    Fail,
+    New,
+    Error,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "ok"),
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "fail"),
+            Status::New => write!(f, "new"),
+            Status::Error => write!(f, "error"),
+        }
+    }
+}
+
+// How the parsing of a part's input was timed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseTime {
+    // Parsing is included in the elapsed time
+    Inline,
+    Timed(Duration),
+    // The input was parsed for an earlier part
+    Shared,
+}
+
+impl ParseTime {
+    fn nanos(&self) -> Option<u128> {
+        match self {
+            ParseTime::Timed(elapsed) => Some(elapsed.as_nanos()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Duration,
+    pub parse: ParseTime,
+    pub status: Status,
+}
+
+pub trait Formatter {
+    fn header(&self, _writer: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn record(&self, writer: &mut dyn Write, record: &Record) -> io::Result<()>;
+}
+
+pub struct Human;
+
+impl Formatter for Human {
+    fn record(&self, writer: &mut dyn Write, record: &Record) -> io::Result<()> {
+        write!(
+            writer,
+            "day{} part{}: {} ",
+            record.day, record.part, record.answer
+        )?;
+
+        match record.parse {
+            ParseTime::Inline => write!(writer, "({:.2?})", record.elapsed)?,
+            ParseTime::Timed(parse) => write!(
+                writer,
+                "(parse {:.2?}, solve {:.2?})",
+                parse, record.elapsed
+            )?,
+            ParseTime::Shared => write!(writer, "(parse shared, solve {:.2?})", record.elapsed)?,
+        }
+
+        match record.status {
+            Status::Ok => writeln!(writer),
+            status => writeln!(writer, " [{}]", status.to_string().to_uppercase()),
+        }
+    }
+}
+
+pub struct JsonLines;
+
+impl JsonLines {
+    fn string(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len() + 2);
+        escaped.push('"');
+
+        for char in value.chars() {
+            match char {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                char if char.is_control() => escaped.push_str(&format!("\\u{:04x}", char as u32)),
+                char => escaped.push(char),
+            }
+        }
+
+        escaped.push('"');
+        escaped
+    }
+}
+
+impl Formatter for JsonLines {
+    fn record(&self, writer: &mut dyn Write, record: &Record) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"elapsed_ns\":{},\"parse_ns\":{},\"status\":{}}}",
+            record.day,
+            record.part,
+            JsonLines::string(&record.answer),
+            record.elapsed.as_nanos(),
+            record
+                .parse
+                .nanos()
+                .map_or("null".to_string(), |nanos| nanos.to_string()),
+            JsonLines::string(&record.status.to_string())
+        )
+    }
+}
+
+pub struct Csv;
+
+impl Csv {
+    fn field(value: &str) -> String {
+        if value.contains(&[',', '"', '\n', '\r'][..]) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+impl Formatter for Csv {
+    fn header(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "day,part,answer,elapsed_ns,status,parse_ns")
+    }
+
+    fn record(&self, writer: &mut dyn Write, record: &Record) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            Csv::field(&record.answer),
+            record.elapsed.as_nanos(),
+            record.status,
+            record
+                .parse
+                .nanos()
+                .map_or(String::new(), |nanos| nanos.to_string())
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    JsonLines,
+    Csv,
+}
+
+impl OutputFormat {
+    pub fn formatter(&self) -> Box<dyn Formatter> {
+        match self {
+            OutputFormat::Human => Box::new(Human),
+            OutputFormat::JsonLines => Box::new(JsonLines),
+            OutputFormat::Csv => Box::new(Csv),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputFormatError(String);
+
+impl Display for OutputFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid output format \"{}\" (expected \"human\", \"json\" or \"csv\")",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for OutputFormatError {}
+
+impl FromStr for OutputFormat {
+    type Err = OutputFormatError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim() {
+            "human" => Ok(OutputFormat::Human),
+            "json" | "jsonl" => Ok(OutputFormat::JsonLines),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(OutputFormatError(input.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(answer: &str, status: Status) -> Record {
+        Record {
+            day: 7,
+            part: 2,
+            answer: answer.to_string(),
+            elapsed: Duration::from_micros(1500),
+            parse: ParseTime::Inline,
+            status,
+        }
+    }
+
+    fn parsed(parse: ParseTime) -> Record {
+        Record {
+            parse,
+            ..record("32", Status::Ok)
+        }
+    }
+
+    fn format(format: OutputFormat, records: &[Record]) -> String {
+        let formatter = format.formatter();
+        let mut output = Vec::new();
+
+        formatter.header(&mut output).unwrap();
+        for record in records {
+            formatter.record(&mut output, record).unwrap();
+        }
+
+        String::from_utf8(output).expect("Not UTF-8")
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("human".parse(), Ok(OutputFormat::Human));
+        assert_eq!("json".parse(), Ok(OutputFormat::JsonLines));
+        assert_eq!("csv".parse(), Ok(OutputFormat::Csv));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_human() {
+        let output = format(
+            OutputFormat::Human,
+            &[
+                record("32", Status::Ok),
+                record("32", Status::Pass),
+                record("", Status::Error),
+                parsed(ParseTime::Timed(Duration::from_millis(2))),
+                parsed(ParseTime::Shared),
+            ],
+        );
+
+        assert_eq!(
+            output,
+            "day7 part2: 32 (1.50ms)\nday7 part2: 32 (1.50ms) [PASS]\nday7 part2:  (1.50ms) [ERROR]\n\
+             day7 part2: 32 (parse 2.00ms, solve 1.50ms)\nday7 part2: 32 (parse shared, solve 1.50ms)\n"
+        );
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let output = format(
+            OutputFormat::JsonLines,
+            &[
+                record("32", Status::Ok),
+                record("a\"b\\c\n", Status::Error),
+                parsed(ParseTime::Timed(Duration::from_millis(2))),
+            ],
+        );
+
+        assert_eq!(
+            output,
+            "{\"day\":7,\"part\":2,\"answer\":\"32\",\"elapsed_ns\":1500000,\"parse_ns\":null,\"status\":\"ok\"}\n\
+             {\"day\":7,\"part\":2,\"answer\":\"a\\\"b\\\\c\\n\",\"elapsed_ns\":1500000,\"parse_ns\":null,\"status\":\"error\"}\n\
+             {\"day\":7,\"part\":2,\"answer\":\"32\",\"elapsed_ns\":1500000,\"parse_ns\":2000000,\"status\":\"ok\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let output = format(
+            OutputFormat::Csv,
+            &[
+                record("32", Status::Ok),
+                record("a,\"b\"", Status::Ok),
+                parsed(ParseTime::Timed(Duration::from_millis(2))),
+            ],
+        );
+
+        assert_eq!(
+            output,
+            "day,part,answer,elapsed_ns,status,parse_ns\n\
+             7,2,32,1500000,ok,\n\
+             7,2,\"a,\"\"b\"\"\",1500000,ok,\n\
+             7,2,32,1500000,ok,2000000\n"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// Seeded xorshift for tests that generate their data, so every run sees the
+// same values
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    // The seed goes through a splitmix64 round first, small seeds would
+    // otherwise start with mostly zero bits and a zero state never changes
+    pub fn new(seed: u64) -> Self {
+        let mut state = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        state ^= state >> 31;
+
+        Random {
+            state: match state {
+                0 => 1,
+                state => state,
+            },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        self.state
+    }
+
+    // In `0..range`, slightly biased towards small values
+    pub fn below(&mut self, range: u64) -> u64 {
+        self.next_u64() % range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_values() {
+        let values = |seed| {
+            let mut random = Random::new(seed);
+            (0..5).map(|_| random.next_u64()).collect::<Vec<u64>>()
+        };
+
+        assert_eq!(values(7), values(7));
+        assert_ne!(values(7), values(8));
+        assert!(values(0).iter().all(|&value| value != 0));
+
+        let mut random = Random::new(3);
+        assert!((0..100).all(|_| random.below(6) < 6));
+    }
+}
@@ -0,0 +1,246 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+pub type Solver = fn(&str) -> String;
+
+// A day's parsed input, shared by its parts
+pub type Parser = Box<dyn Fn(&str) -> Box<dyn Any>>;
+pub type ParsedSolver = Box<dyn Fn(&dyn Any) -> String>;
+
+#[derive(Default)]
+pub struct Registry {
+    inputs: BTreeMap<u8, &'static str>,
+    solvers: BTreeMap<(u8, u8), Solver>,
+    parsers: BTreeMap<u8, Parser>,
+    parsed_solvers: BTreeMap<(u8, u8), ParsedSolver>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn add_day(&mut self, day: u8, input: &'static str) -> &mut Self {
+        self.inputs.insert(day, input);
+        self
+    }
+
+    pub fn add(&mut self, day: u8, part: u8, solver: Solver) -> &mut Self {
+        self.solvers.insert((day, part), solver);
+        self
+    }
+
+    // Parts added with `add_parsed` share the input parsed once by `parser`
+    pub fn add_parser<T: Any>(&mut self, day: u8, parser: fn(&str) -> T) -> &mut Self {
+        self.parsers
+            .insert(day, Box::new(move |input| Box::new(parser(input))));
+        self
+    }
+
+    pub fn add_parsed<T: Any>(&mut self, day: u8, part: u8, solver: fn(&T) -> String) -> &mut Self {
+        let solver = move |parsed: &dyn Any| {
+            solver(
+                parsed
+                    .downcast_ref()
+                    .expect("Something went wrong, the parser returns another type!"),
+            )
+        };
+
+        self.parsed_solvers.insert((day, part), Box::new(solver));
+        self
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = u8> + '_ {
+        self.inputs.keys().copied()
+    }
+
+    pub fn input(&self, day: u8) -> Option<&'static str> {
+        self.inputs.get(&day).copied()
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<Solver> {
+        self.solvers.get(&(day, part)).copied()
+    }
+
+    pub fn parts(&self, day: u8) -> impl Iterator<Item = (u8, Solver)> + '_ {
+        self.solvers
+            .range((day, u8::MIN)..=(day, u8::MAX))
+            .map(|(&(_, part), &solver)| (part, solver))
+    }
+
+    pub fn parser(&self, day: u8) -> Option<&Parser> {
+        self.parsers.get(&day)
+    }
+
+    pub fn parsed_parts(&self, day: u8) -> impl Iterator<Item = (u8, &ParsedSolver)> + '_ {
+        self.parsed_solvers
+            .range((day, u8::MIN)..=(day, u8::MAX))
+            .map(|(&(_, part), solver)| (part, solver))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Range(u8, u8),
+}
+
+impl DaySelection {
+    pub fn contains(&self, day: u8) -> bool {
+        match *self {
+            DaySelection::All => true,
+            DaySelection::Range(first, last) => first <= day && day <= last,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionError(String);
+
+impl fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid day selection \"{}\" (expected \"all\", \"N\" or \"N..M\")",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for SelectionError {}
+
+// Ranges are inclusive on both ends, so "1..25" selects every puzzle day.
+impl FromStr for DaySelection {
+    type Err = SelectionError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || SelectionError(input.to_string());
+        let day = |value: &str| value.trim().parse::<u8>().map_err(|_| error());
+        let input = input.trim();
+
+        if input == "all" {
+            return Ok(DaySelection::All);
+        }
+
+        let selection = match input.find("..") {
+            Some(index) => {
+                let last = &input[index + 2..];
+                let last = last.strip_prefix('=').unwrap_or(last);
+
+                DaySelection::Range(day(&input[..index])?, day(last)?)
+            }
+            None => {
+                let day = day(input)?;
+
+                DaySelection::Range(day, day)
+            }
+        };
+
+        match selection {
+            DaySelection::Range(first, last) if first > last => Err(error()),
+            selection => Ok(selection),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry
+            .add_day(2, "2")
+            .add(2, 2, |input| format!("{}-2", input))
+            .add(2, 1, |input| format!("{}-1", input))
+            .add_day(1, "1")
+            .add(1, 1, |input| format!("{}-1", input));
+        registry
+    }
+
+    #[test]
+    fn test_registry_days_are_sorted() {
+        assert_eq!(registry().days().collect::<Vec<u8>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_registry_parts() {
+        let registry = registry();
+        let input = registry.input(2).unwrap();
+
+        let result: Vec<(u8, String)> = registry
+            .parts(2)
+            .map(|(part, solver)| (part, solver(input)))
+            .collect();
+
+        assert_eq!(result, vec![(1, "2-1".to_string()), (2, "2-2".to_string())]);
+    }
+
+    #[test]
+    fn test_registry_get() {
+        let registry = registry();
+
+        assert_eq!(
+            registry.get(1, 1).map(|solver| solver("x")),
+            Some("x-1".to_string())
+        );
+        assert!(registry.get(1, 2).is_none());
+        assert!(registry.input(3).is_none());
+    }
+
+    #[test]
+    fn test_registry_parsed_parts() {
+        let mut registry = Registry::new();
+        registry
+            .add_day(3, "1,2,3")
+            .add_parser(3, |input| {
+                input
+                    .split(',')
+                    .map(|number| number.parse().unwrap())
+                    .collect::<Vec<u32>>()
+            })
+            .add_parsed(3, 2, |numbers: &Vec<u32>| numbers.len().to_string())
+            .add_parsed(3, 1, |numbers: &Vec<u32>| {
+                numbers.iter().sum::<u32>().to_string()
+            });
+
+        let parsed = registry.parser(3).unwrap()(registry.input(3).unwrap());
+        let result: Vec<(u8, String)> = registry
+            .parsed_parts(3)
+            .map(|(part, solver)| (part, solver(parsed.as_ref())))
+            .collect();
+
+        assert_eq!(result, vec![(1, "6".to_string()), (2, "3".to_string())]);
+        assert_eq!(registry.parts(3).count(), 0);
+        assert!(registry.parser(1).is_none());
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!("all".parse(), Ok(DaySelection::All));
+        assert_eq!("7".parse(), Ok(DaySelection::Range(7, 7)));
+        assert_eq!("1..25".parse(), Ok(DaySelection::Range(1, 25)));
+        assert_eq!("3..=5".parse(), Ok(DaySelection::Range(3, 5)));
+    }
+
+    #[test]
+    fn test_parse_invalid_selection() {
+        assert!("".parse::<DaySelection>().is_err());
+        assert!("x".parse::<DaySelection>().is_err());
+        assert!("5..3".parse::<DaySelection>().is_err());
+        assert!("1..".parse::<DaySelection>().is_err());
+    }
+
+    #[test]
+    fn test_selection_contains() {
+        let selection = DaySelection::Range(3, 5);
+
+        assert!(!selection.contains(2));
+        assert!(selection.contains(3));
+        assert!(selection.contains(5));
+        assert!(!selection.contains(6));
+        assert!(DaySelection::All.contains(25));
+    }
+}
@@ -1,8 +1,11 @@
 use std::fmt::Display;
 use std::io::{stdout, Write};
-use std::time::Instant;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
 
+use crate::answers::Answers;
 use crate::instant::BasicInstant;
+use crate::output::{Formatter, ParseTime, Record, Status};
 
 pub fn run<S, R>(name: &str, solver: S)
 where
@@ -31,9 +34,172 @@ where
     .expect("Something went wrong writing the solution!")
 }
 
+pub fn run_formatted<S, R>(
+    formatter: &dyn Formatter,
+    day: u8,
+    part: u8,
+    answers: Option<&Answers>,
+    parse: ParseTime,
+    solver: S,
+) -> Record
+where
+    R: Display,
+    S: Fn() -> R,
+{
+    raw_run_formatted(
+        stdout(),
+        Instant::now(),
+        formatter,
+        (day, part),
+        answers,
+        parse,
+        solver,
+    )
+}
+
+fn raw_run_formatted<W, N, S, R>(
+    mut writer: W,
+    now: N,
+    formatter: &dyn Formatter,
+    (day, part): (u8, u8),
+    answers: Option<&Answers>,
+    parse: ParseTime,
+    solver: S,
+) -> Record
+where
+    W: Write,
+    N: BasicInstant,
+    S: Fn() -> R,
+    R: Display,
+{
+    // A panicking solver is reported as an error record so the remaining parts still run
+    let solution = panic::catch_unwind(AssertUnwindSafe(solver));
+    let elapsed = now.elapsed();
+
+    let (answer, status) = match solution {
+        Ok(solution) => {
+            let answer = solution.to_string();
+            let status = match answers {
+                Some(answers) => answers.check(part, &answer),
+                None => Status::Ok,
+            };
+
+            (answer, status)
+        }
+        Err(_) => (String::new(), Status::Error),
+    };
+    let record = Record {
+        day,
+        part,
+        answer,
+        elapsed,
+        parse,
+        status,
+    };
+
+    formatter
+        .record(&mut writer, &record)
+        .expect("Something went wrong writing the solution!");
+
+    record
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+// Parses the input for the runner, `None` when the parser panics
+pub fn parse_timed<P, T>(parser: P) -> Option<Parsed<T>>
+where
+    P: FnOnce() -> T,
+{
+    let now = Instant::now();
+    let value = panic::catch_unwind(AssertUnwindSafe(parser)).ok()?;
+
+    Some(Parsed {
+        value,
+        elapsed: now.elapsed(),
+    })
+}
+
+pub fn run_with_parse<P, T, S, R>(name: &str, parser: P, solver: S) -> Parsed<T>
+where
+    P: FnOnce() -> T,
+    S: Fn(&T) -> R,
+    R: Display,
+{
+    raw_run_with_parse::<_, Instant, _, _, _, _>(stdout(), name, parser, solver)
+}
+
+pub fn run_parsed<T, S, R>(name: &str, parsed: &Parsed<T>, solver: S)
+where
+    S: Fn(&T) -> R,
+    R: Display,
+{
+    raw_run_parsed(stdout(), Instant::now(), name, parsed, solver);
+}
+
+fn raw_run_with_parse<W, N, P, T, S, R>(
+    mut writer: W,
+    name: &str,
+    parser: P,
+    solver: S,
+) -> Parsed<T>
+where
+    W: Write,
+    N: BasicInstant,
+    P: FnOnce() -> T,
+    S: Fn(&T) -> R,
+    R: Display,
+{
+    let now = N::now();
+    let value = parser();
+    let parsed = Parsed {
+        value,
+        elapsed: now.elapsed(),
+    };
+
+    let now = N::now();
+    let solution = solver(&parsed.value);
+
+    writeln!(
+        &mut writer,
+        "{}: {} (parse {:.2?}, solve {:.2?})",
+        name,
+        solution,
+        parsed.elapsed,
+        now.elapsed()
+    )
+    .expect("Something went wrong writing the solution!");
+
+    parsed
+}
+
+fn raw_run_parsed<W, N, T, S, R>(mut writer: W, now: N, name: &str, parsed: &Parsed<T>, solver: S)
+where
+    W: Write,
+    N: BasicInstant,
+    S: Fn(&T) -> R,
+    R: Display,
+{
+    let solution = solver(&parsed.value);
+
+    writeln!(
+        &mut writer,
+        "{}: {} (parse shared, solve {:.2?})",
+        name,
+        solution,
+        now.elapsed()
+    )
+    .expect("Something went wrong writing the solution!")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::instant;
+    use crate::output::{Csv, JsonLines};
 
     use super::*;
     use std::time::Duration;
@@ -56,4 +222,103 @@ mod tests {
 
         assert_eq!(output, "test: 3066 (10.00ms)\n");
     }
+
+    #[test]
+    fn test_run_formatted() {
+        instant::fake::with_fake_elapsed(Duration::from_millis(10));
+        let mut output = Vec::new();
+
+        let record = raw_run_formatted(
+            &mut output,
+            instant::fake::Instant::now(),
+            &JsonLines,
+            (1, 2),
+            None,
+            ParseTime::Inline,
+            || 3066,
+        );
+        let output = String::from_utf8(output).expect("Not UTF-8");
+
+        assert_eq!(record.answer, "3066");
+        assert_eq!(record.status, Status::Ok);
+        assert_eq!(
+            output,
+            "{\"day\":1,\"part\":2,\"answer\":\"3066\",\"elapsed_ns\":10000000,\"parse_ns\":null,\"status\":\"ok\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_run_formatted_panic() {
+        instant::fake::with_fake_elapsed(Duration::from_millis(10));
+        let mut output = Vec::new();
+
+        let record = raw_run_formatted(
+            &mut output,
+            instant::fake::Instant::now(),
+            &Csv,
+            (1, 1),
+            None,
+            ParseTime::Inline,
+            || -> u32 { panic!("unsolvable") },
+        );
+        let output = String::from_utf8(output).expect("Not UTF-8");
+
+        assert_eq!(record.status, Status::Error);
+        assert_eq!(output, "1,1,,10000000,error,\n");
+    }
+
+    #[test]
+    fn test_run_formatted_checks_answers() {
+        instant::fake::with_fake_elapsed(Duration::from_millis(10));
+        let answers: Answers = "part1 = \"3066\"\npart2 = \"1\"".parse().unwrap();
+        let statuses: Vec<Status> = (1..=3)
+            .map(|part| {
+                raw_run_formatted(
+                    Vec::new(),
+                    instant::fake::Instant::now(),
+                    &Csv,
+                    (1, part),
+                    Some(&answers),
+                    ParseTime::Inline,
+                    || 3066,
+                )
+                .status
+            })
+            .collect();
+
+        assert_eq!(statuses, vec![Status::Pass, Status::Fail, Status::New]);
+    }
+
+    #[test]
+    fn test_run_with_parse() {
+        instant::fake::with_fake_elapsed(Duration::from_millis(10));
+        let mut output = Vec::new();
+
+        let parsed = raw_run_with_parse::<_, instant::fake::Instant, _, _, _, _>(
+            &mut output,
+            "part1",
+            || vec![1721, 979, 366],
+            |input: &Vec<i32>| input.iter().sum::<i32>(),
+        );
+        raw_run_parsed(
+            &mut output,
+            instant::fake::Instant::now(),
+            "part2",
+            &parsed,
+            |input| input.len(),
+        );
+        let output = String::from_utf8(output).expect("Not UTF-8");
+
+        assert_eq!(parsed.value, vec![1721, 979, 366]);
+        assert_eq!(
+            output,
+            "part1: 3066 (parse 10.00ms, solve 10.00ms)\npart2: 3 (parse shared, solve 10.00ms)\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_timed() {
+        assert_eq!(parse_timed(|| 42).map(|parsed| parsed.value), Some(42));
+        assert_eq!(parse_timed(|| -> u32 { panic!("unparsable") }), None);
+    }
 }
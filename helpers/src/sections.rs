@@ -0,0 +1,329 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionKey {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for SectionKey {
+    fn from(index: usize) -> Self {
+        SectionKey::Index(index)
+    }
+}
+
+impl From<&str> for SectionKey {
+    fn from(name: &str) -> Self {
+        SectionKey::Name(name.to_string())
+    }
+}
+
+impl Display for SectionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionKey::Index(index) => write!(f, "section {}", index + 1),
+            SectionKey::Name(name) => write!(f, "section \"{}\"", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionError<E> {
+    Missing(SectionKey),
+    Parse {
+        section: SectionKey,
+        line: usize,
+        text: String,
+        error: E,
+    },
+}
+
+impl<E: Display> Display for SectionError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionError::Missing(section) => write!(f, "missing {}", section),
+            SectionError::Parse {
+                section,
+                line,
+                text,
+                error,
+            } => write!(
+                f,
+                "{}, line {}: cannot parse \"{}\": {}",
+                section, line, text, error
+            ),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for SectionError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SectionError::Missing(_) => None,
+            SectionError::Parse { error, .. } => Some(error),
+        }
+    }
+}
+
+// A run of non-blank lines. When the first line ends with a colon, like
+// "your ticket:" or "Tile 2311:", it names the section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub index: usize,
+    pub name: Option<&'a str>,
+    line: usize,
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+    // Every line, header included
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    // Every line but the header
+    pub fn body(&self) -> String {
+        self.body_lines().join("\n")
+    }
+
+    fn body_lines(&self) -> &[&'a str] {
+        match self.name {
+            Some(_) => &self.lines[1..],
+            None => &self.lines,
+        }
+    }
+
+    fn body_line(&self) -> usize {
+        match self.name {
+            Some(_) => self.line + 1,
+            None => self.line,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sections<'a> {
+    sections: Vec<Section<'a>>,
+}
+
+impl<'a> Sections<'a> {
+    // Lines are trimmed and any number of blank lines separates two sections
+    pub fn new(input: &'a str) -> Self {
+        let mut sections: Vec<Section<'a>> = Vec::new();
+        let mut current: Option<Section<'a>> = None;
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                sections.extend(current.take());
+                continue;
+            }
+
+            let section = current.get_or_insert_with(|| Section {
+                index: sections.len(),
+                name: line.strip_suffix(':'),
+                line: index + 1,
+                lines: Vec::new(),
+            });
+            section.lines.push(line);
+        }
+
+        sections.extend(current);
+
+        Sections { sections }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Section<'a>> {
+        self.sections.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Section<'a>> {
+        self.sections.get(index)
+    }
+
+    pub fn named(&self, name: &str) -> Option<&Section<'a>> {
+        self.sections
+            .iter()
+            .find(|section| section.name == Some(name))
+    }
+
+    // A section found by name is parsed without its header, one found by
+    // index is parsed whole.
+    fn find<E>(&self, key: &SectionKey) -> Result<(&[&'a str], usize), SectionError<E>> {
+        let missing = || SectionError::Missing(key.clone());
+
+        match key {
+            SectionKey::Index(index) => self
+                .get(*index)
+                .map(|section| (&section.lines[..], section.line))
+                .ok_or_else(missing),
+            SectionKey::Name(name) => self
+                .named(name)
+                .map(|section| (section.body_lines(), section.body_line()))
+                .ok_or_else(missing),
+        }
+    }
+
+    pub fn parse<T, K>(&self, key: K) -> Result<T, SectionError<T::Err>>
+    where
+        T: FromStr,
+        K: Into<SectionKey>,
+    {
+        let key = key.into();
+        let (lines, line) = self.find(&key)?;
+        let text = lines.join("\n");
+
+        text.parse().map_err(|error| SectionError::Parse {
+            section: key,
+            line,
+            text,
+            error,
+        })
+    }
+
+    // Each line of the section on its own, errors point at the failing line
+    pub fn parse_lines<T, R, K>(&self, key: K) -> Result<R, SectionError<T::Err>>
+    where
+        T: FromStr,
+        R: FromIterator<T>,
+        K: Into<SectionKey>,
+    {
+        let key = key.into();
+        let (lines, line) = self.find(&key)?;
+
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                text.parse().map_err(|error| SectionError::Parse {
+                    section: key.clone(),
+                    line: line + index,
+                    text: text.to_string(),
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    // Every section as a whole, for inputs made of repeated records
+    pub fn parse_all<T, R>(&self) -> Result<R, SectionError<T::Err>>
+    where
+        T: FromStr,
+        R: FromIterator<T>,
+    {
+        self.sections
+            .iter()
+            .map(|section| self.parse(section.index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::ParseIntError;
+
+    use super::*;
+
+    const INPUT: &str = "
+        class: 1-3 or 5-7
+        row: 6-11 or 33-44
+
+        your ticket:
+        7,1,14
+
+
+        nearby tickets:
+        7,3,47
+        40,x,50
+    ";
+
+    #[derive(Debug)]
+    struct Ticket(Vec<usize>);
+
+    impl FromStr for Ticket {
+        type Err = ParseIntError;
+
+        fn from_str(input: &str) -> Result<Self, Self::Err> {
+            input
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<usize>, _>>()
+                .map(Ticket)
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let sections = Sections::new(INPUT);
+
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections.get(0).unwrap().name, None);
+        assert_eq!(
+            sections.get(0).unwrap().text(),
+            "class: 1-3 or 5-7\nrow: 6-11 or 33-44"
+        );
+        assert_eq!(sections.get(1).unwrap().name, Some("your ticket"));
+        assert_eq!(sections.named("your ticket").unwrap().body(), "7,1,14");
+        assert_eq!(sections.named("nearby tickets").unwrap().index, 2);
+    }
+
+    #[test]
+    fn test_parse_named() {
+        let sections = Sections::new(INPUT);
+        let ticket: Ticket = sections.parse("your ticket").unwrap();
+
+        assert_eq!(ticket.0, vec![7, 1, 14]);
+    }
+
+    #[test]
+    fn test_parse_indexed() {
+        let sections = Sections::new("1\n2\n\n3");
+
+        assert_eq!(sections.parse::<String, _>(0), Ok("1\n2".to_string()));
+        assert!(sections.parse::<usize, _>(0).is_err());
+        assert_eq!(sections.parse_lines(0), Ok(vec![1, 2]));
+        assert_eq!(Sections::new("1\n\n3").parse_all(), Ok(vec![1, 3]));
+    }
+
+    #[test]
+    fn test_parse_lines_reports_line() {
+        let error = Sections::new(INPUT)
+            .parse_lines::<Ticket, Vec<Ticket>, _>("nearby tickets")
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "section \"nearby tickets\", line 11: cannot parse \"40,x,50\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_missing_section() {
+        let sections = Sections::new(INPUT);
+
+        assert_eq!(
+            sections
+                .parse::<Ticket, _>("my ticket")
+                .err()
+                .unwrap()
+                .to_string(),
+            "missing section \"my ticket\""
+        );
+        assert_eq!(
+            sections.parse::<Ticket, _>(3).err().unwrap().to_string(),
+            "missing section 4"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::env;
+use std::fs;
+use std::io::{self, stdin, Read};
+use std::path::PathBuf;
+
+pub const INPUT_ENV: &str = "AOC_INPUT";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    Bundled(&'static str),
+}
+
+impl InputSource {
+    // An explicit argument wins over the environment variable, and both accept
+    // "-" to read from stdin. Without either, the bundled input is used.
+    pub fn select(argument: Option<&str>, variable: Option<&str>, bundled: &'static str) -> Self {
+        match argument.or(variable).map(|value| value.trim()) {
+            Some("-") => InputSource::Stdin,
+            Some(path) if !path.is_empty() => InputSource::File(PathBuf::from(path)),
+            _ => InputSource::Bundled(bundled),
+        }
+    }
+
+    pub fn from_args(bundled: &'static str) -> Self {
+        let argument = env::args().nth(1);
+        let variable = env::var(INPUT_ENV).ok();
+
+        InputSource::select(argument.as_deref(), variable.as_deref(), bundled)
+    }
+
+    pub fn is_bundled(&self) -> bool {
+        matches!(self, InputSource::Bundled(_))
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        self.read_from(stdin())
+    }
+
+    fn read_from<R: Read>(&self, mut reader: R) -> io::Result<String> {
+        match self {
+            InputSource::File(path) => fs::read_to_string(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                reader.read_to_string(&mut input)?;
+                Ok(input)
+            }
+            InputSource::Bundled(input) => Ok(input.to_string()),
+        }
+    }
+}
+
+pub fn load_input(bundled: &'static str) -> String {
+    InputSource::from_args(bundled)
+        .read()
+        .expect("Something went wrong reading the input!")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_select_bundled() {
+        assert_eq!(
+            InputSource::select(None, None, "bundled"),
+            InputSource::Bundled("bundled")
+        );
+        assert_eq!(
+            InputSource::select(Some(" "), None, "bundled"),
+            InputSource::Bundled("bundled")
+        );
+    }
+
+    #[test]
+    fn test_select_argument_before_variable() {
+        assert_eq!(
+            InputSource::select(Some("a.txt"), Some("b.txt"), ""),
+            InputSource::File(PathBuf::from("a.txt"))
+        );
+        assert_eq!(
+            InputSource::select(None, Some("b.txt"), ""),
+            InputSource::File(PathBuf::from("b.txt"))
+        );
+    }
+
+    #[test]
+    fn test_select_stdin() {
+        assert_eq!(InputSource::select(Some("-"), None, ""), InputSource::Stdin);
+        assert_eq!(InputSource::select(None, Some("-"), ""), InputSource::Stdin);
+    }
+
+    #[test]
+    fn test_read_bundled() {
+        let source = InputSource::Bundled("1\n2\n");
+
+        assert_eq!(source.read().unwrap(), "1\n2\n");
+    }
+
+    #[test]
+    fn test_read_stdin() {
+        let source = InputSource::Stdin;
+
+        assert_eq!(source.read_from(Cursor::new("3\n4\n")).unwrap(), "3\n4\n");
+    }
+
+    #[test]
+    fn test_read_file() {
+        let path = env::temp_dir().join("helpers_source_test_read_file.txt");
+        fs::write(&path, "5\n6\n").unwrap();
+
+        let result = InputSource::File(path.clone()).read();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap(), "5\n6\n");
+    }
+
+    #[test]
+    fn test_read_missing_file() {
+        let source = InputSource::File(PathBuf::from("/nonexistent/input.txt"));
+
+        assert!(source.read().is_err());
+    }
+}